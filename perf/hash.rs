@@ -6,7 +6,10 @@ use helpers::Memory;
 use helpers::PythonExt;
 use helpers::generate_bytes;
 
+use pyo3::types::IntoPyDict;
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyBytes;
+use pyo3::types::PyList;
 
 macro_rules! bench_hash {
     ($name:ident, $import:ident, $memory:expr) => {
@@ -24,6 +27,25 @@ macro_rules! bench_hash {
     };
 }
 
+macro_rules! bench_hash_many {
+    ($name:ident, $import:ident, $memory:expr) => {
+        #[divan::bench]
+        fn $name(bencher: Bencher) {
+            python!(py, {
+                let seed: u64 = 42;
+                let payloads = (0u64..24)
+                    .map(|i| PyBytes::new(py, &generate_bytes(seed.wrapping_add(i), $memory)))
+                    .collect::<Vec<_>>();
+
+                let items = PyList::new(py, payloads)?;
+                let hash_many = py.$import()?.call1((seed,))?.getattr("hash_many")?;
+
+                bencher.bench_local(|| hash_many.call1((&items,)));
+            })
+        }
+    };
+}
+
 macro_rules! bench_hash_async {
     ($name:ident, $import:ident, $memory:expr) => {
         #[divan::bench]
@@ -44,6 +66,29 @@ macro_rules! bench_hash_async {
     };
 }
 
+macro_rules! bench_hash_many_async {
+    ($name:ident, $import:ident, $memory:expr) => {
+        #[divan::bench]
+        fn $name(bencher: Bencher) {
+            python!(py, {
+                let seed: u64 = 42;
+                let payloads = (0u64..24)
+                    .map(|i| PyBytes::new(py, &generate_bytes(seed.wrapping_add(i), $memory)))
+                    .collect::<Vec<_>>();
+
+                let items = PyList::new(py, payloads)?;
+                let asyncio = py.import_asyncio()?;
+                let hash_many_async = py.$import()?.call1((seed,))?.getattr("hash_many_async")?;
+                let asyncio_loop = asyncio.getattr("new_event_loop")?.call0()?;
+                let run_until_complete = asyncio_loop.getattr("run_until_complete")?;
+
+                asyncio.call_method1("set_event_loop", (&asyncio_loop,))?;
+                bencher.bench_local(|| run_until_complete.call1((hash_many_async.call1((&items,))?,)));
+            })
+        }
+    };
+}
+
 macro_rules! bench_hash_async_batch {
     ($name:ident, $import:ident, $memory:expr) => {
         #[divan::bench]
@@ -77,6 +122,48 @@ macro_rules! bench_hash_async_batch {
     };
 }
 
+macro_rules! bench_hash_async_concurrent {
+    ($name:ident, $import:ident, $memory:expr) => {
+        #[divan::bench(args = [1, 2, 4, 8, 16, 32])]
+        fn $name(bencher: Bencher, concurrency: u64) {
+            python!(py, {
+                let seed: u64 = 42;
+                let payloads = (0..concurrency)
+                    .map(|i| PyBytes::new(py, &generate_bytes(seed.wrapping_add(i), $memory)))
+                    .collect::<Vec<_>>();
+
+                let asyncio = py.import_asyncio()?;
+                let gather = py.import_gather()?;
+                let asyncio_loop = asyncio.getattr("new_event_loop")?.call0()?;
+                let create_task = asyncio_loop.getattr("create_task")?;
+                let run_until_complete = asyncio_loop.getattr("run_until_complete")?;
+                // offloading every hash measures the worker threads, whatever the calibrated threshold is
+                let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+                let hash_async = py
+                    .$import()?
+                    .call((seed,), Some(&kwargs))?
+                    .getattr("hash_async")?;
+
+                asyncio.call_method1("set_event_loop", (&asyncio_loop,))?;
+
+                // eager tasks are only available from Python 3.12, and otherwise start once the loop runs
+                if let Ok(eager_task_factory) = asyncio.getattr("eager_task_factory") {
+                    asyncio_loop.call_method1("set_task_factory", (eager_task_factory,))?;
+                }
+                bencher.bench_local(|| {
+                    let tasks = payloads
+                        .iter()
+                        .flat_map(|bytes| hash_async.call1((bytes,)))
+                        .flat_map(|coroutine| create_task.call1((coroutine,)))
+                        .collect::<Vec<_>>();
+
+                    run_until_complete.call1((gather.call1((tasks,))?,))
+                });
+            })
+        }
+    };
+}
+
 bench_hash!(hash32_small, import_gxhash32, Memory::B64);
 bench_hash!(hash32, import_gxhash32, Memory::KiB64);
 bench_hash!(hash64_small, import_gxhash64, Memory::B64);
@@ -84,6 +171,13 @@ bench_hash!(hash64, import_gxhash64, Memory::KiB64);
 bench_hash!(hash128_small, import_gxhash128, Memory::B64);
 bench_hash!(hash128, import_gxhash128, Memory::KiB64);
 
+bench_hash_many!(hash32_many_small, import_gxhash32, Memory::B64);
+bench_hash_many!(hash32_many, import_gxhash32, Memory::KiB64);
+bench_hash_many!(hash64_many_small, import_gxhash64, Memory::B64);
+bench_hash_many!(hash64_many, import_gxhash64, Memory::KiB64);
+bench_hash_many!(hash128_many_small, import_gxhash128, Memory::B64);
+bench_hash_many!(hash128_many, import_gxhash128, Memory::KiB64);
+
 bench_hash_async!(hash32_async_small, import_gxhash32, Memory::B64);
 bench_hash_async!(hash32_async, import_gxhash32, Memory::KiB64);
 bench_hash_async!(hash64_async_small, import_gxhash64, Memory::B64);
@@ -101,6 +195,20 @@ bench_hash_async_batch!(hash128_async_batch_small, import_gxhash128, Memory::B64
 bench_hash_async_batch!(hash128_async_batch, import_gxhash128, Memory::KiB64);
 bench_hash_async_batch!(hash128_async_batch_large, import_gxhash128, Memory::MiB4);
 
+bench_hash_async_concurrent!(hash32_async_concurrent, import_gxhash32, Memory::MiB4);
+bench_hash_async_concurrent!(hash64_async_concurrent, import_gxhash64, Memory::MiB4);
+bench_hash_async_concurrent!(hash128_async_concurrent, import_gxhash128, Memory::MiB4);
+
+bench_hash_many_async!(hash32_many_async_small, import_gxhash32, Memory::B64);
+bench_hash_many_async!(hash32_many_async, import_gxhash32, Memory::KiB64);
+bench_hash_many_async!(hash32_many_async_large, import_gxhash32, Memory::MiB4);
+bench_hash_many_async!(hash64_many_async_small, import_gxhash64, Memory::B64);
+bench_hash_many_async!(hash64_many_async, import_gxhash64, Memory::KiB64);
+bench_hash_many_async!(hash64_many_async_large, import_gxhash64, Memory::MiB4);
+bench_hash_many_async!(hash128_many_async_small, import_gxhash128, Memory::B64);
+bench_hash_many_async!(hash128_many_async, import_gxhash128, Memory::KiB64);
+bench_hash_many_async!(hash128_many_async_large, import_gxhash128, Memory::MiB4);
+
 fn main() {
     divan::main();
 }
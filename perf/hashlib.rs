@@ -9,6 +9,7 @@ use helpers::generate_bytes;
 use pyo3::intern;
 use pyo3::types::IntoPyDict;
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyBytes;
 
 macro_rules! bench_hashlib_digest {
     ($name:ident, $import:ident, $memory:expr) => {
@@ -63,6 +64,31 @@ macro_rules! bench_hashlib_update {
     };
 }
 
+macro_rules! bench_hashlib_many_updates {
+    ($name:ident, $import:ident, $memory:expr) => {
+        #[divan::bench(args = [1_000, 10_000, 100_000])]
+        fn $name(bencher: Bencher, updates: usize) {
+            python!(py, {
+                let seed = 42;
+                let bytes = PyBytes::new(py, &generate_bytes(seed, $memory));
+                let kwargs = [("seed", seed)].into_py_dict(py)?;
+                let constructor = py.$import()?;
+
+                bencher.bench_local(|| -> pyo3::PyResult<_> {
+                    let hasher = constructor.call((), Some(&kwargs))?;
+                    let update = hasher.getattr(intern!(py, "update"))?;
+
+                    for _ in 0..updates {
+                        update.call1((&bytes,))?;
+                    }
+
+                    hasher.call_method0(intern!(py, "digest"))
+                });
+            })
+        }
+    };
+}
+
 macro_rules! bench_hashlib_copy {
     ($name:ident, $import:ident, $memory:expr) => {
         #[divan::bench]
@@ -136,9 +162,16 @@ bench_hashlib_update!(gxhashlib64_update, import_gxhashlib64, Memory::KiB64);
 bench_hashlib_update!(gxhashlib128_update_small, import_gxhashlib128, Memory::B64);
 bench_hashlib_update!(gxhashlib128_update, import_gxhashlib128, Memory::KiB64);
 
+bench_hashlib_many_updates!(gxhashlib32_many_updates, import_gxhashlib32, Memory::B64);
+bench_hashlib_many_updates!(gxhashlib64_many_updates, import_gxhashlib64, Memory::B64);
+bench_hashlib_many_updates!(gxhashlib128_many_updates, import_gxhashlib128, Memory::B64);
+
 bench_hashlib_copy!(gxhashlib32_copy, import_gxhashlib32, Memory::KiB64);
 bench_hashlib_copy!(gxhashlib64_copy, import_gxhashlib64, Memory::KiB64);
 bench_hashlib_copy!(gxhashlib128_copy, import_gxhashlib128, Memory::KiB64);
+bench_hashlib_copy!(gxhashlib32_copy_large, import_gxhashlib32, Memory::MiB4);
+bench_hashlib_copy!(gxhashlib64_copy_large, import_gxhashlib64, Memory::MiB4);
+bench_hashlib_copy!(gxhashlib128_copy_large, import_gxhashlib128, Memory::MiB4);
 
 bench_hashlib_new!(gxhashlib_new32_small, "gxhash32", Memory::B64);
 bench_hashlib_new!(gxhashlib_new32, "gxhash32", Memory::KiB64);
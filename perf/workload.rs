@@ -2,11 +2,15 @@
 mod helpers;
 
 use divan::Bencher;
+use helpers::Memory;
 use helpers::PythonExt;
 use helpers::generate_bytes;
 
 use pyo3::types::PyAnyMethods;
 use pyo3::types::PyBytes;
+use pyo3::types::PyList;
+use pyo3::types::PyModule;
+use std::ffi::CString;
 
 struct PseudoRNG {
     state: u64,
@@ -115,6 +119,92 @@ fn workload_simulation(bencher: Bencher) {
     });
 }
 
+#[divan::bench]
+fn workload_simulation_many(bencher: Bencher) {
+    python!(py, {
+        let seed = 42u64;
+        let payload_batches_size = 200;
+        let warmup_payload = PyList::new(py, [PyBytes::new(py, &vec![0u8; 32 << 20])])?;
+        let mut rng = PseudoRNG::new(seed);
+        let batch_sizes: Vec<_> = (0..payload_batches_size).map(|_| random_batch_size(&mut rng)).collect();
+        let delays: Vec<_> = (0..payload_batches_size).map(|_| random_delay(&mut rng)).collect();
+        let payload_batches = batch_sizes
+            .iter()
+            .map(|&batch_size| PyList::new(py, generate_batch(py, &mut rng, batch_size)))
+            .collect::<pyo3::PyResult<Vec<_>>>()?;
+
+        let hash_many_async = py.import_gxhash64()?.call1((seed,))?.getattr("hash_many_async")?;
+        let asyncio = py.import_asyncio()?;
+        let asyncio_loop = asyncio.getattr("new_event_loop")?.call0()?;
+        let run_until_complete = asyncio_loop.getattr("run_until_complete")?;
+
+        asyncio.call_method1("set_event_loop", (&asyncio_loop,))?;
+
+        for _ in 0..50 {
+            run_until_complete.call1((hash_many_async.call1((&warmup_payload,))?,))?;
+        }
+
+        bencher.bench_local(|| -> pyo3::PyResult<()> {
+            for (payloads, &delay) in payload_batches.iter().zip(&delays) {
+                run_until_complete.call1((hash_many_async.call1((payloads,))?,))?;
+                std::thread::sleep(std::time::Duration::from_nanos_u128(delay));
+            }
+
+            Ok(())
+        });
+    });
+}
+
+fn workload_simulation_small(bencher: Bencher, hash_small: &str) {
+    python!(py, {
+        let seed = 42u64;
+        let payload_batches_size = 200;
+        let mut rng = PseudoRNG::new(seed);
+        let batch_sizes: Vec<_> = (0..payload_batches_size).map(|_| random_batch_size(&mut rng)).collect();
+        let payload_batches = batch_sizes
+            .iter()
+            .map(|&batch_size| {
+                PyList::new(
+                    py,
+                    (0..batch_size).map(|_| PyBytes::new(py, &generate_bytes(rng.next_u64(), Memory::B64))),
+                )
+            })
+            .collect::<pyo3::PyResult<Vec<_>>>()?;
+
+        let source = [
+            "import asyncio",
+            "",
+            "async def hash_batch(hash_small, payloads):",
+            "    await asyncio.gather(*map(hash_small, payloads))",
+        ];
+        let hash_batch =
+            PyModule::from_code(py, &CString::new(source.join("\n"))?, c"batch.py", c"batch")?.getattr("hash_batch")?;
+        let hash_small = py.import_gxhash64()?.call1((seed,))?.getattr(hash_small)?;
+        let asyncio = py.import_asyncio()?;
+        let asyncio_loop = asyncio.getattr("new_event_loop")?.call0()?;
+        let run_until_complete = asyncio_loop.getattr("run_until_complete")?;
+
+        asyncio.call_method1("set_event_loop", (&asyncio_loop,))?;
+        bencher.bench_local(|| -> pyo3::PyResult<()> {
+            for payloads in &payload_batches {
+                run_until_complete.call1((hash_batch.call1((&hash_small, payloads))?,))?;
+            }
+
+            Ok(())
+        });
+    });
+}
+
+#[divan::bench]
+fn workload_simulation_uncoalesced(bencher: Bencher) {
+    workload_simulation_small(bencher, "hash_async");
+}
+
+#[divan::bench]
+fn workload_simulation_coalesced(bencher: Bencher) {
+    workload_simulation_small(bencher, "hash_coalesced");
+}
+
 fn main() {
     divan::main();
 }
@@ -4,4 +4,5 @@ from gxhash.core import GxHash32 as GxHash32
 from gxhash.core import GxHash64 as GxHash64
 from gxhash.core import GxHash128 as GxHash128
 from gxhash.core import GxHashAsyncError as GxHashAsyncError
+from gxhash.core import GxHashTimeoutError as GxHashTimeoutError
 from gxhash.core import __doc__ as __doc__
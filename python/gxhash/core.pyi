@@ -1,13 +1,38 @@
-from typing import NewType, Protocol, TypeVar
+import sys
+from collections.abc import AsyncIterable, Awaitable, Callable, Iterable, Iterator, Sequence
+from concurrent.futures import Future
+from os import PathLike
+from types import TracebackType
+from typing import Any, Literal, NewType, Protocol, TypeVar
+
+from gxhash.buffer import Buffer
+
+if sys.version_info >= (3, 13):
+    from types import CapsuleType
+else:
+    from typing_extensions import CapsuleType
+
+if sys.version_info >= (3, 11):
+    from typing import ParamSpec, Self
+else:
+    from typing_extensions import ParamSpec, Self
 
 __doc__: str
 T_co = TypeVar("T_co", covariant=True, bound=int)
+P = ParamSpec("P")
+R = TypeVar("R")
 Uint32 = NewType("Uint32", int)
 Uint64 = NewType("Uint64", int)
 Uint128 = NewType("Uint128", int)
 
 class Hasher(Protocol[T_co]):
-    def __init__(self, *, seed: int) -> None:
+    def __init__(
+        self,
+        *,
+        seed: int,
+        offload_threshold: int | Literal["always", "never"] | None = None,
+        executor: CapsuleType | None = None,
+    ) -> None:
         """
         Summary
         -------
@@ -20,6 +45,14 @@ class Hasher(Protocol[T_co]):
         seed (`int`)
             a signed 64-bit seed for the hasher [-2^63, 2^63)
 
+        offload_threshold (`int | Literal["always", "never"]?`)
+            the input size in bytes from which asynchronous hashes are offloaded to a worker thread,
+            or whether they should `"always"` or `"never"` be offloaded; defaults to `offload_threshold()`
+
+        executor (`CapsuleType?`)
+            a `gxhash.core.executor` capsule to offload asynchronous hashes to instead of the runtime of `gxhash`,
+            such as one exported by another extension sharing its thread pool
+
         Example
         -------
         ```python
@@ -30,16 +63,18 @@ class Hasher(Protocol[T_co]):
         ```
         """
 
-    def hash(self, data: bytes, /) -> T_co:
+    def hash(self, data: Buffer, /) -> T_co:
         """
         Summary
         -------
         Hashes `data` to an `int`.
         This method has less overhead than `hash_async`.
+        Any contiguous buffer, such as `bytearray`, `memoryview` or `array.array`, is hashed as bytes without a copy.
+        Inputs of 4 MiB or more are hashed with the GIL released, so other threads can make progress in the meantime.
 
         Parameters
         ----------
-        data (`bytes`)
+        data (`Buffer`)
             input data
 
         Returns
@@ -58,7 +93,145 @@ class Hasher(Protocol[T_co]):
         ```
         """
 
-    async def hash_async(self, data: bytes, /) -> T_co:
+    def hash_many(self, items: Sequence[Buffer], /) -> list[T_co]:
+        """
+        Summary
+        -------
+        Hashes every buffer in `items` to an `int` in a single call.
+        This method releases the GIL while hashing and avoids the per-call overhead of `hash` for many small inputs.
+
+        Parameters
+        ----------
+        items (`Sequence[Buffer]`)
+            input data
+
+        Returns
+        -------
+        hashes (`list[int]`)
+            the hashes of the input data, in the same order as `items`
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash64
+        >>> hasher = GxHash64(seed=1234)
+        >>> hasher.hash_many([bytes(range(256))])
+        [12522596144082598891]
+
+        ```
+        """
+
+    def hash_parallel(self, data: Buffer, /, *, chunk_size: int = 4 << 20) -> T_co:
+        """
+        Summary
+        -------
+        Hashes a single very large `data` to an `int` by splitting it across the worker threads.
+        The input is cut into chunks of `chunk_size` bytes, which are hashed into the leaves of a binary tree.
+        Adjacent nodes are then hashed in pairs, and the root is hashed together with the size of the input.
+        Leaves and nodes are prefixed with a different tag byte, so that a leaf can never be mistaken for a node.
+        Called from a thread of the runtime, such as a `RuntimeExecutor` task, the leaves are hashed on that thread.
+        The output only depends on `data`, `chunk_size` and the seed, so it is the same for every number of threads and
+        on every platform, but it is NOT the same as the output of `hash`. The GIL is released while hashing.
+
+        Parameters
+        ----------
+        data (`Buffer`)
+            input data
+
+        chunk_size (`int`)
+            the size of the chunks in bytes, which is part of the output and must be at least 4 KiB
+
+        Returns
+        -------
+        hash (`int`)
+            the tree hash of the input data
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash64
+        >>> hasher = GxHash64(seed=1234)
+        >>> hasher.hash_parallel(bytes(range(256)) * 64, chunk_size=4096)
+        1802481588919932823
+
+        ```
+        """
+
+    def hash_submit(self, data: Buffer, /) -> Future[T_co]:
+        """
+        Summary
+        -------
+        Hashes `data` to an `int` on the runtime, without requiring an event loop.
+        This method lets threaded code overlap large hashes, and follows the same `offload_threshold` as `hash_async`.
+        Inputs below the threshold are hashed before returning, as an already completed future.
+        So are all inputs submitted from a thread of the runtime, such as a `RuntimeExecutor` task, to avoid a deadlock.
+        Cancelling the future skips the hash if it has not started yet.
+        When offloaded hashes are limited, the calling thread waits for a slot before submitting the hash.
+
+        Parameters
+        ----------
+        data (`Buffer`)
+            input data
+
+        Returns
+        -------
+        future (`Future[int]`)
+            a future resolving to the hash of the input data
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash64
+        >>> hasher = GxHash64(seed=1234)
+        >>> hasher.hash_submit(bytes(range(256))).result()
+        12522596144082598891
+
+        ```
+        """
+
+    def hash_coalesced(self, data: Buffer, /) -> Awaitable[T_co]:
+        """
+        Summary
+        -------
+        Hashes `data` to an `int` asynchronously, together with the other calls made within the same event loop tick.
+        The calls are gathered and hashed in a single native call on the next tick, which makes many small concurrent
+        hashes cheaper than awaiting a coroutine for each of them with `hash_async`.
+        Inputs are never offloaded, so this method should be reserved for small inputs.
+        It must be called from a running `asyncio` event loop.
+
+        Parameters
+        ----------
+        data (`Buffer`)
+            input data
+
+        Returns
+        -------
+        hash (`Awaitable[int]`)
+            a future resolving to the hash of the input data
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash128
+        >>> from asyncio import gather, run
+        >>> hasher = GxHash128(seed=1234)
+        >>> async def main():
+        ...     return await gather(hasher.hash_coalesced(bytes(range(256))))
+        >>> run(main())
+        [117181821629540739333037011138327886827]
+
+        ```
+        """
+
+    async def hash_async(
+        self,
+        data: Buffer,
+        /,
+        *,
+        timeout: float | None = None,
+        progress: Callable[[int], object] | None = None,
+        progress_interval: int = 16 << 20,
+    ) -> T_co:
         """
         Summary
         -------
@@ -66,12 +239,25 @@ class Hasher(Protocol[T_co]):
         This method allows you to compute multiple hashes with true multi-threaded parallelism.
         If called sequentially, this method is slightly less performant than the default `hash` method.
         Otherwise, this variant offers the highest throughput.
+        The buffer of `data` stays exported until the hash completes, so a `bytearray` cannot be resized in the meantime.
+        Cancelling the call skips the hash if it has not started yet, but a hash that has started runs to completion.
+        As a single buffer is hashed in one pass, its `progress` is only reported once the whole buffer is hashed.
+        It can be awaited from either `asyncio` or `trio`, and so from `anyio` on both backends.
 
         Parameters
         ----------
-        data (`bytes`)
+        data (`Buffer`)
             input data
 
+        timeout (`float?`)
+            the number of seconds to wait for an offloaded hash before raising `GxHashTimeoutError`
+
+        progress (`Callable[[int], object]?`)
+            called with the number of bytes hashed so far, from the thread hashing them
+
+        progress_interval (`int?`)
+            the number of bytes processed between two calls to `progress`
+
         Returns
         -------
         hash (`int`)
@@ -89,6 +275,274 @@ class Hasher(Protocol[T_co]):
         ```
         """
 
+    async def hash_file_async(
+        self,
+        path: str | PathLike[str],
+        /,
+        *,
+        timeout: float | None = None,
+        progress: Callable[[int], object] | None = None,
+        progress_interval: int = 16 << 20,
+    ) -> T_co:
+        """
+        Summary
+        -------
+        Hashes the contents of the file at `path` to an `int` asynchronously, with the same output as `hash`.
+        The file is opened, read and hashed on a worker thread, so the event loop is never blocked on I/O,
+        and its contents are never copied into Python `bytes`. Large files are mapped into memory where supported,
+        so truncating a file while it is hashed can crash the process with `SIGBUS`, as with any mapped file.
+        Files count towards `max_in_flight_bytes` with the size they have when the call is awaited.
+        With a `progress` callable, the file is read into memory instead of mapped, so that the reads can be reported.
+        It can be awaited from either `asyncio` or `trio`, and so from `anyio` on both backends.
+
+        Parameters
+        ----------
+        path (`str | PathLike[str]`)
+            the path of the file to hash
+
+        timeout (`float?`)
+            the number of seconds to wait for the file to be hashed before raising `GxHashTimeoutError`
+
+        progress (`Callable[[int], object]?`)
+            called with the number of bytes read so far, and once more when the file is hashed, from the worker thread
+
+        progress_interval (`int?`)
+            the number of bytes read between two calls to `progress`
+
+        Returns
+        -------
+        hash (`int`)
+            the hash of the contents of the file
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash64
+        >>> from asyncio import run
+        >>> from pathlib import Path
+        >>> from tempfile import TemporaryDirectory
+        >>> hasher = GxHash64(seed=1234)
+        >>> with TemporaryDirectory() as directory:
+        ...     path = Path(directory, "file")
+        ...     _ = path.write_bytes(bytes(range(256)))
+        ...     run(hasher.hash_file_async(path))
+        12522596144082598891
+
+        ```
+        """
+
+    async def hash_stream_async(self, chunks: AsyncIterable[Buffer], /) -> T_co:
+        """
+        Summary
+        -------
+        Hashes every chunk of the asynchronous iterable `chunks` to a single `int` as they arrive.
+        This method consumes streams such as HTTP bodies or file readers without collecting them into a single buffer.
+        The hash only depends on the concatenated chunks, and is equal to the digest of a `streaming=True` HASH object
+        from `gxhash.hashlib` with the same seed, but NOT to the hash of the concatenated chunks returned by `hash`.
+        The chunks are hashed on the thread of the event loop, which releases the GIL for large chunks.
+        It can be awaited from either `asyncio` or `trio`, and so from `anyio` on both backends.
+
+        Parameters
+        ----------
+        chunks (`AsyncIterable[Buffer]`)
+            input data
+
+        Returns
+        -------
+        hash (`int`)
+            the hash of the concatenated chunks
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash64
+        >>> from asyncio import run
+        >>> hasher = GxHash64(seed=1234)
+        >>> async def chunks():
+        ...     yield bytes(range(128))
+        ...     yield bytes(range(128, 256))
+        >>> run(hasher.hash_stream_async(chunks()))
+        12269285236729178523
+
+        ```
+        """
+
+    async def hash_many_async(self, items: Sequence[Buffer], /, *, timeout: float | None = None) -> list[T_co]:
+        """
+        Summary
+        -------
+        Hashes every buffer in `items` to an `int` asynchronously.
+        Large batches are split across the worker threads, so the items are hashed with true multi-threaded parallelism.
+        Small batches are hashed inline, as it is cheaper than dispatching them to another thread.
+        Cancelling the call stops the worker threads after the items they are currently hashing.
+        It can be awaited from either `asyncio` or `trio`, and so from `anyio` on both backends.
+
+        Parameters
+        ----------
+        items (`Sequence[Buffer]`)
+            input data
+
+        timeout (`float?`)
+            the number of seconds to wait for an offloaded batch before raising `GxHashTimeoutError`
+
+        Returns
+        -------
+        hashes (`list[int]`)
+            the hashes of the input data, in the same order as `items`
+
+        Example
+        -------
+        ```python
+        >>> from gxhash import GxHash128
+        >>> from asyncio import run
+        >>> hasher = GxHash128(seed=1234)
+        >>> run(hasher.hash_many_async([bytes(range(256))]))
+        [117181821629540739333037011138327886827]
+
+        ```
+        """
+
+def calibrate() -> int:
+    """
+    Summary
+    -------
+    Measures the cost of hashing inline against the cost of offloading to a worker thread on this machine.
+    The crossover point becomes the default `offload_threshold` of the hashers offloading to the runtime of `gxhash`,
+    whereas hashers given an `executor` measure it against that executor instead.
+    This happens automatically in the background on first use, but can be repeated when the load on the machine changes.
+
+    Returns
+    -------
+    offload_threshold (`int`)
+        the input size in bytes from which asynchronous hashes are offloaded by default
+    """
+
+def offload_threshold() -> int:
+    """
+    Summary
+    -------
+    Returns the input size in bytes from which asynchronous hashes are offloaded by default.
+    The threshold is measured with `calibrate` if it has not been measured yet.
+    Hashers measure it on a background thread on their first asynchronous hash, and offload from 4 MiB until then.
+
+    Returns
+    -------
+    offload_threshold (`int`)
+        the input size in bytes from which asynchronous hashes are offloaded by default
+    """
+
+def configure_runtime(
+    *,
+    worker_threads: int | None = None,
+    max_in_flight: int | None = None,
+    max_in_flight_bytes: int | None = None,
+) -> None:
+    """
+    Summary
+    -------
+    Configures the runtime that asynchronous hashes are offloaded to.
+    The runtime is only started on first use, and must be stopped with `shutdown_runtime` before it can be reconfigured.
+    Once a limit is reached, further asynchronous hashes wait for a slot without blocking the event loop.
+
+    Parameters
+    ----------
+    worker_threads (`int?`)
+        the number of threads used to hash asynchronously;
+        defaults to the `GXHASH_WORKER_THREADS` environment variable, or the number of CPU cores if it is not set
+
+    max_in_flight (`int?`)
+        the maximum number of asynchronous hashes offloaded at once, where a `hash_many_async` call counts once;
+        defaults to no limit
+
+    max_in_flight_bytes (`int?`)
+        the maximum total size in bytes of the inputs offloaded at once, where a larger input waits for every slot;
+        defaults to no limit
+    """
+
+def shutdown_runtime() -> None:
+    """
+    Summary
+    -------
+    Stops the runtime that asynchronous hashes are offloaded to.
+    In-flight hashes still complete, and the runtime is started again on next use.
+    Forked child processes automatically start their own runtime, so this is not needed before forking.
+    """
+
+runtime: object
+"""
+Summary
+-------
+Deprecated since the runtime is started on first use, and will be removed in a future release.
+Accessing it warns with a `DeprecationWarning`; use `configure_runtime` and `shutdown_runtime` instead.
+"""
+
+def queue_depth() -> int:
+    """
+    Summary
+    -------
+    Returns the number of asynchronous hashes waiting for a slot before they can be offloaded.
+    This is always zero unless `configure_runtime` was given a limit.
+
+    Returns
+    -------
+    queue_depth (`int`)
+        the number of asynchronous hashes waiting for a slot
+    """
+
+def executor() -> CapsuleType:
+    """
+    Summary
+    -------
+    Exports the runtime that asynchronous hashes are offloaded to as a `gxhash.core.executor` capsule.
+    Other extensions can use the capsule to offload their blocking work to the same thread pool.
+    The capsule always refers to the current runtime, so it remains valid across `shutdown_runtime` and forks.
+
+    Returns
+    -------
+    executor (`CapsuleType`)
+        a capsule implementing the `GxHashExecutor` C ABI
+    """
+
+class RuntimeExecutor:
+    """
+    Summary
+    -------
+    A `concurrent.futures.Executor` that runs callables on the threads of the runtime.
+    Callables hold the GIL while they run, so they only run in parallel when they release it.
+    This is the case of `hash` for large inputs, so the executor can be used to overlap them.
+    Shutting the executor down does not stop the runtime, which is shared with every hasher.
+
+    Example
+    -------
+    ```python
+    >>> from gxhash import GxHash64
+    >>> from gxhash.core import RuntimeExecutor
+    >>> hasher = GxHash64(seed=1234)
+    >>> with RuntimeExecutor() as executor:
+    ...     list(executor.map(hasher.hash, [bytes(range(256))]))
+    [12522596144082598891]
+
+    ```
+    """
+
+    def submit(self, fn: Callable[P, R], /, *args: P.args, **kwargs: P.kwargs) -> Future[R]: ...
+    def map(
+        self,
+        fn: Callable[..., R],
+        /,
+        *iterables: Iterable[Any],
+        timeout: float | None = None,
+        chunksize: int = 1,
+    ) -> Iterator[R]: ...
+    def shutdown(self, wait: bool = True, *, cancel_futures: bool = False) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> None: ...
+
 class GxHashAsyncError(Exception):
     """
     Summary
@@ -96,6 +550,13 @@ class GxHashAsyncError(Exception):
     This error is raised when an asynchronous hash operation fails.
     """
 
+class GxHashTimeoutError(GxHashAsyncError):
+    """
+    Summary
+    -------
+    This error is raised when an asynchronous hash operation does not complete within its `timeout`.
+    """
+
 class GxHash32(Hasher[Uint32]):
     """
     Summary
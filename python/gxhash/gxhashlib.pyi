@@ -19,6 +19,9 @@ def new(
     data: Buffer = b"",
     *,
     seed: int = 0,
+    streaming: bool = False,
+    memory_budget: int | None = None,
+    lazy: bool = False,
     usedforsecurity: bool = False,
     **kwargs: object,
 ) -> HASH:
@@ -27,7 +30,8 @@ def new(
     -------
     Returns a hash object implementing the given hash algorithm; optionally initialised with `data`.
     Note that GxHash is not an incremental hasher,
-    and all inputs provided to the `update` method are accumulated internally.
+    and all inputs provided to the `update` method are accumulated internally unless `streaming` is enabled.
+    Accumulating takes linear time overall, and the digest is cached until the next call to `update`.
 
     Parameters
     ----------
@@ -40,6 +44,18 @@ def new(
     seed (`int?`)
         a signed 64-bit seed for the hasher [-2^63, 2^63)
 
+    streaming (`bool?`)
+        hash all inputs incrementally in constant memory;
+        the digest only depends on the concatenated input but differs from the non-streaming digest
+
+    memory_budget (`int?`)
+        the number of bytes accumulated in memory before the input spills to an anonymous temporary file;
+        the digest is unchanged, and this parameter is ignored if `streaming` is enabled
+
+    lazy (`bool?`)
+        borrow `data` until it is hashed instead of copying it, so that it must not be mutated in the meantime;
+        `bytes` are never copied, as they cannot be mutated
+
     usedforsecurity (`bool?`)
         this parameter has no effect and is only present for compatibility with `hashlib`
 
@@ -59,17 +75,64 @@ def new(
     ```
     """
 
+def install() -> None:
+    """
+    Summary
+    -------
+    Registers the GxHash algorithms with the standard `hashlib` module,
+    so that `hashlib.new`, `hashlib.file_digest` and the `algorithms_available` set support them for code that only
+    takes an algorithm name. `algorithms_guaranteed` is left as it is, as GxHash is not part of every Python build.
+    Installing more than once has no further effect.
+
+    Example
+    -------
+    ```python
+    >>> import hashlib
+    >>> from gxhash.hashlib import install, uninstall
+    >>> install()
+    >>> hashlib.new("gxhash32", b"hello", seed=42).hexdigest()
+    '9ffaa800'
+    >>> "gxhash32" in hashlib.algorithms_available
+    True
+    >>> uninstall()
+
+    ```
+    """
+
+def uninstall() -> None:
+    """
+    Summary
+    -------
+    Removes the GxHash algorithms registered by `install` from the standard `hashlib` module,
+    restoring `hashlib.new` unless it was replaced again after `install`.
+
+    Example
+    -------
+    ```python
+    >>> import hashlib
+    >>> from gxhash.hashlib import install, uninstall
+    >>> install()
+    >>> uninstall()
+    >>> "gxhash32" in hashlib.algorithms_available
+    False
+
+    ```
+    """
+
 def file_digest(
     fileobj: BytesIOLike | FileLike,
     digest: str | Callable[[], HASH],
     *,
     seed: int = 0,
+    progress: Callable[[int], object] | None = None,
+    progress_interval: int = 16 << 20,
     **kwargs: object,
 ) -> HASH:
     """
     Summary
     -------
     Returns a hash object implementing the given hash algorithm, with the hash of the file-like object.
+    The buffer of a `BytesIO` is hashed without a copy, so it cannot be resized until the hash object drops it.
 
     Parameters
     ----------
@@ -82,6 +145,12 @@ def file_digest(
     seed (`int?`)
         a signed 64-bit seed for the hasher [-2^63, 2^63); only used if `digest` is a string
 
+    progress (`Callable[[int], object]?`)
+        called with the number of bytes read so far, and once more when the file is hashed; raising aborts the hash
+
+    progress_interval (`int?`)
+        the number of bytes read between two calls to `progress`
+
     Returns
     -------
     gxhash (`HASH`)
@@ -100,13 +169,23 @@ def file_digest(
     ```
     """
 
-def gxhash32(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False, **kwargs: object) -> HASH:
+def gxhash32(
+    data: Buffer = b"",
+    *,
+    seed: int = 0,
+    streaming: bool = False,
+    memory_budget: int | None = None,
+    lazy: bool = False,
+    usedforsecurity: bool = False,
+    **kwargs: object,
+) -> HASH:
     """
     Summary
     -------
     Returns a GxHash32 hash object; optionally initialised with `data`.
     Note that GxHash is not an incremental hasher,
-    and all inputs provided to the `update` method are accumulated internally.
+    and all inputs provided to the `update` method are accumulated internally unless `streaming` is enabled.
+    Accumulating takes linear time overall, and the digest is cached until the next call to `update`.
 
     Parameters
     ----------
@@ -116,6 +195,18 @@ def gxhash32(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False
     seed (`int?`)
         a signed 64-bit seed for the hasher [-2^63, 2^63)
 
+    streaming (`bool?`)
+        hash all inputs incrementally in constant memory;
+        the digest only depends on the concatenated input but differs from the non-streaming digest
+
+    memory_budget (`int?`)
+        the number of bytes accumulated in memory before the input spills to an anonymous temporary file;
+        the digest is unchanged, and this parameter is ignored if `streaming` is enabled
+
+    lazy (`bool?`)
+        borrow `data` until it is hashed instead of copying it, so that it must not be mutated in the meantime;
+        `bytes` are never copied, as they cannot be mutated
+
     usedforsecurity (`bool?`)
         this parameter has no effect and is only present for compatibility with `hashlib`
 
@@ -135,13 +226,23 @@ def gxhash32(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False
     ```
     """
 
-def gxhash64(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False, **kwargs: object) -> HASH:
+def gxhash64(
+    data: Buffer = b"",
+    *,
+    seed: int = 0,
+    streaming: bool = False,
+    memory_budget: int | None = None,
+    lazy: bool = False,
+    usedforsecurity: bool = False,
+    **kwargs: object,
+) -> HASH:
     """
     Summary
     -------
     Returns a GxHash64 hash object; optionally initialised with `data`.
     Note that GxHash is not an incremental hasher,
-    and all inputs provided to the `update` method are accumulated internally.
+    and all inputs provided to the `update` method are accumulated internally unless `streaming` is enabled.
+    Accumulating takes linear time overall, and the digest is cached until the next call to `update`.
 
     Parameters
     ----------
@@ -151,6 +252,18 @@ def gxhash64(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False
     seed (`int?`)
         a signed 64-bit seed for the hasher [-2^63, 2^63)
 
+    streaming (`bool?`)
+        hash all inputs incrementally in constant memory;
+        the digest only depends on the concatenated input but differs from the non-streaming digest
+
+    memory_budget (`int?`)
+        the number of bytes accumulated in memory before the input spills to an anonymous temporary file;
+        the digest is unchanged, and this parameter is ignored if `streaming` is enabled
+
+    lazy (`bool?`)
+        borrow `data` until it is hashed instead of copying it, so that it must not be mutated in the meantime;
+        `bytes` are never copied, as they cannot be mutated
+
     usedforsecurity (`bool?`)
         this parameter has no effect and is only present for compatibility with `hashlib`
 
@@ -170,13 +283,23 @@ def gxhash64(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False
     ```
     """
 
-def gxhash128(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False, **kwargs: object) -> HASH:
+def gxhash128(
+    data: Buffer = b"",
+    *,
+    seed: int = 0,
+    streaming: bool = False,
+    memory_budget: int | None = None,
+    lazy: bool = False,
+    usedforsecurity: bool = False,
+    **kwargs: object,
+) -> HASH:
     """
     Summary
     -------
     Returns a GxHash128 hash object; optionally initialised with `data`.
     Note that GxHash is not an incremental hasher,
-    and all inputs provided to the `update` method are accumulated internally.
+    and all inputs provided to the `update` method are accumulated internally unless `streaming` is enabled.
+    Accumulating takes linear time overall, and the digest is cached until the next call to `update`.
 
     Parameters
     ----------
@@ -186,6 +309,18 @@ def gxhash128(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = Fals
     seed (`int?`)
         a signed 64-bit seed for the hasher [-2^63, 2^63)
 
+    streaming (`bool?`)
+        hash all inputs incrementally in constant memory;
+        the digest only depends on the concatenated input but differs from the non-streaming digest
+
+    memory_budget (`int?`)
+        the number of bytes accumulated in memory before the input spills to an anonymous temporary file;
+        the digest is unchanged, and this parameter is ignored if `streaming` is enabled
+
+    lazy (`bool?`)
+        borrow `data` until it is hashed instead of copying it, so that it must not be mutated in the meantime;
+        `bytes` are never copied, as they cannot be mutated
+
     usedforsecurity (`bool?`)
         this parameter has no effect and is only present for compatibility with `hashlib`
 
@@ -5,4 +5,6 @@ from gxhash.gxhashlib import file_digest as file_digest
 from gxhash.gxhashlib import gxhash32 as gxhash32
 from gxhash.gxhashlib import gxhash64 as gxhash64
 from gxhash.gxhashlib import gxhash128 as gxhash128
+from gxhash.gxhashlib import install as install
 from gxhash.gxhashlib import new as new
+from gxhash.gxhashlib import uninstall as uninstall
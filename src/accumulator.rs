@@ -0,0 +1,229 @@
+use crate::buffer::ContiguousBuffer;
+use crate::buffer::PyBufferExt;
+use crate::file;
+use crate::file::Contents;
+
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::sync::Arc;
+
+enum Chunk {
+    Borrowed(ContiguousBuffer),
+    Owned(Vec<u8>),
+    Shared(Arc<Contents>),
+}
+
+impl Chunk {
+    /// Returns the number of bytes that this chunk holds in memory, which excludes the buffers owned by Python objects
+    fn resident(&self) -> usize {
+        match self {
+            Self::Borrowed(_) => 0,
+            Self::Owned(bytes) => bytes.len(),
+            Self::Shared(shared) => match &**shared {
+                Contents::Read(bytes) => bytes.len(),
+                #[cfg(unix)]
+                Contents::Mapped(_) => 0,
+            },
+        }
+    }
+
+    /// Returns whether this chunk is still shared with a copy
+    fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared(shared) if Arc::strong_count(shared) > 1)
+    }
+
+    /// Returns a chunk with the same bytes for a copy, sharing the bytes owned by this chunk instead of copying them
+    ///
+    /// Borrowed buffers are copied, as they may be mutated by their owners.
+    fn share(&mut self) -> Self {
+        match self {
+            Self::Borrowed(buffer) => Self::Owned(buffer.as_bytes().to_vec()),
+            Self::Owned(bytes) => {
+                let shared = Arc::new(Contents::Read(std::mem::take(bytes)));
+                *self = Self::Shared(Arc::clone(&shared));
+                Self::Shared(shared)
+            }
+            Self::Shared(shared) => Self::Shared(Arc::clone(shared)),
+        }
+    }
+}
+
+impl PyBufferExt for Chunk {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(buffer) => buffer.as_bytes(),
+            Self::Owned(bytes) => bytes,
+            Self::Shared(shared) => shared.as_bytes(),
+        }
+    }
+}
+
+/// The input that did not fit in the memory budget, which is appended to an anonymous temporary file
+struct Spill {
+    writer: BufWriter<File>,
+    length: usize,
+}
+
+impl Spill {
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.writer.write_all(data)?;
+        self.length += data.len();
+        Ok(())
+    }
+}
+
+/// The whole input of an `Accumulator`, either in memory, concatenated for a single hash, or mapped from the file that
+/// it spilled to
+pub(crate) enum Contiguous<'a> {
+    Memory(&'a [u8]),
+    Concatenated(Vec<u8>),
+    Spilled(Contents),
+}
+
+impl PyBufferExt for Contiguous<'_> {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Memory(bytes) => bytes,
+            Self::Concatenated(bytes) => bytes,
+            Self::Spilled(contents) => contents.as_bytes(),
+        }
+    }
+}
+
+/// An append-only store of the input of a HASH object, which is only concatenated once it is hashed
+///
+/// Updates are appended to the last chunk owned by the store, so that many small updates copy every byte a constant
+/// number of times. The chunks are concatenated into a single one when the input is hashed, and later updates extend it.
+/// Once the chunks held in memory would exceed the memory `budget`, the whole input spills to a temporary file instead.
+/// Copies share the chunks accumulated so far, which are immutable once shared and only concatenated for a single hash
+/// while they are still shared, so that forking a store costs constant memory however long its input is.
+#[derive(Default)]
+pub(crate) struct Accumulator {
+    chunks: Vec<Chunk>,
+    budget: Option<usize>,
+    spill: Option<Spill>,
+}
+
+impl Accumulator {
+    pub(crate) fn new(budget: Option<usize>) -> Self {
+        Self {
+            chunks: Vec::new(),
+            budget,
+            spill: None,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let spilled = self.spill.as_ref().map_or(0, |spill| spill.length);
+        spilled + self.chunks.iter().map(|chunk| chunk.as_bytes().len()).sum::<usize>()
+    }
+
+    fn resident(&self) -> usize {
+        self.chunks.iter().map(Chunk::resident).sum()
+    }
+
+    fn exceeds_budget(&self, resident: usize) -> bool {
+        self.budget.is_some_and(|budget| resident > budget)
+    }
+
+    /// Moves the whole input to the temporary file, which is created on the first spill
+    fn spill(&mut self) -> io::Result<&mut Spill> {
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            spill @ None => spill.insert(Spill {
+                writer: BufWriter::new(file::temporary()?),
+                length: 0,
+            }),
+        };
+
+        for chunk in self.chunks.drain(..) {
+            spill.write(chunk.as_bytes())?;
+        }
+
+        Ok(spill)
+    }
+
+    /// Appends a buffer without copying it unless the input spilled, so that it must not be mutated until it is hashed
+    pub(crate) fn borrow(&mut self, buffer: ContiguousBuffer) -> io::Result<()> {
+        if self.spill.is_some() {
+            return self.append(buffer.as_bytes());
+        }
+
+        self.chunks.push(Chunk::Borrowed(buffer));
+        Ok(())
+    }
+
+    pub(crate) fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        if self.spill.is_some() || self.exceeds_budget(self.resident() + data.len()) {
+            return self.spill()?.write(data);
+        }
+
+        match self.chunks.last_mut() {
+            Some(Chunk::Owned(chunk)) => chunk.extend_from_slice(data),
+            _ => self.chunks.push(Chunk::Owned(data.to_vec())),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the whole input, concatenating the chunks first unless there is at most one of them
+    ///
+    /// An input that spilled, or that would exceed the budget once concatenated, is mapped from the temporary file.
+    pub(crate) fn contiguous(&mut self) -> io::Result<Contiguous<'_>> {
+        if self.spill.is_some() || (self.chunks.len() > 1 && self.exceeds_budget(self.len())) {
+            let spill = self.spill()?;
+            spill.writer.flush()?;
+            let length = spill.length;
+            return file::map(spill.writer.get_mut(), length).map(Contiguous::Spilled);
+        }
+
+        if self.chunks.len() > 1 {
+            let contiguous = self.concatenate();
+
+            // keeping it would give every fork a copy of the shared prefix, and the digest is cached until an update
+            if self.chunks.iter().any(Chunk::is_shared) {
+                return Ok(Contiguous::Concatenated(contiguous));
+            }
+
+            self.chunks = vec![Chunk::Owned(contiguous)];
+        }
+
+        Ok(Contiguous::Memory(
+            self.chunks.first().map_or(&[], PyBufferExt::as_bytes),
+        ))
+    }
+
+    fn concatenate(&self) -> Vec<u8> {
+        let mut contiguous = Vec::with_capacity(self.len());
+        self.chunks
+            .iter()
+            .for_each(|chunk| contiguous.extend_from_slice(chunk.as_bytes()));
+        contiguous
+    }
+
+    /// Copies the store without copying the bytes that it owns, which both stores then share
+    ///
+    /// An input that spilled is shared as a mapping of the temporary file, as the bytes written to it are never modified.
+    pub(crate) fn copy(&mut self) -> io::Result<Self> {
+        let chunks = match &mut self.spill {
+            Some(spill) => {
+                spill.writer.flush()?;
+                let length = spill.length;
+                vec![Chunk::Shared(Arc::new(file::map(spill.writer.get_mut(), length)?))]
+            }
+            None => self.chunks.iter_mut().map(Chunk::share).collect(),
+        };
+
+        Ok(Self {
+            chunks,
+            budget: self.budget,
+            spill: None,
+        })
+    }
+}
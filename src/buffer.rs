@@ -1,11 +1,76 @@
-use pyo3::buffer::PyBuffer;
+use pyo3::buffer::PyUntypedBuffer;
+use pyo3::exceptions::PyBufferError;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyBytes;
+use pyo3::Borrowed;
+use pyo3::FromPyObject;
+use pyo3::PyAny;
+use pyo3::PyErr;
+use pyo3::Python;
 
 pub(crate) trait PyBufferExt {
     fn as_bytes(&self) -> &[u8];
 }
 
-impl PyBufferExt for PyBuffer<u8> {
+impl PyBufferExt for PyUntypedBuffer {
     fn as_bytes(&self) -> &[u8] {
         unsafe { std::slice::from_raw_parts(self.buf_ptr() as *const u8, self.len_bytes()) }
     }
 }
+
+/// A C-contiguous buffer of any item format, which is hashed as the bytes of its items
+pub(crate) struct ContiguousBuffer(PyUntypedBuffer);
+
+impl ContiguousBuffer {
+    /// Returns whether the buffer is exported by a `bytes` object, which cannot be mutated
+    pub(crate) fn is_bytes(&self, py: Python<'_>) -> bool {
+        self.0
+            .obj(py)
+            .is_some_and(PyAnyMethods::is_exact_instance_of::<PyBytes>)
+    }
+}
+
+impl PyBufferExt for ContiguousBuffer {
+    fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl FromPyObject<'_, '_> for ContiguousBuffer {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        let buffer = PyUntypedBuffer::get(&obj)?;
+
+        if !buffer.is_c_contiguous() {
+            return Err(PyBufferError::new_err("buffer is not contiguous"));
+        }
+
+        Ok(Self(buffer))
+    }
+}
+
+pub(crate) enum PyBytesLike<'a> {
+    Bytes(&'a [u8]),
+    Buffer(ContiguousBuffer),
+}
+
+impl PyBufferExt for PyBytesLike<'_> {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Bytes(bytes) => bytes,
+            Self::Buffer(buffer) => buffer.as_bytes(),
+        }
+    }
+}
+
+impl<'a> FromPyObject<'a, '_> for PyBytesLike<'a> {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, '_, PyAny>) -> Result<Self, Self::Error> {
+        match <&[u8]>::extract(obj) {
+            Ok(bytes) => Ok(Self::Bytes(bytes)),
+            Err(_) => ContiguousBuffer::extract(obj).map(Self::Buffer),
+        }
+    }
+}
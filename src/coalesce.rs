@@ -0,0 +1,114 @@
+use crate::buffer::ContiguousBuffer;
+use crate::buffer::PyBufferExt;
+
+use pyo3::intern;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyCFunction;
+use pyo3::Bound;
+use pyo3::IntoPyObject;
+use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyResult;
+use pyo3::Python;
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+type Requests = Vec<(ContiguousBuffer, Py<PyAny>)>;
+
+/// The requests made to a hasher within one tick of an event loop
+struct Batch {
+    event_loop: Py<PyAny>,
+    requests: Mutex<Option<Requests>>,
+}
+
+impl Batch {
+    fn take(&self) -> Option<Requests> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+
+    /// Adds a request unless the batch was already flushed, in which case the request is handed back
+    fn push(&self, request: (ContiguousBuffer, Py<PyAny>)) -> Result<(), (ContiguousBuffer, Py<PyAny>)> {
+        match self.requests.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+            Some(requests) => {
+                requests.push(request);
+                Ok(())
+            }
+            None => Err(request),
+        }
+    }
+}
+
+/// Gathers the requests made to a hasher within one tick of an event loop, and hashes them together on the next tick
+///
+/// Each request resolves an `asyncio.Future`, which is cheaper to create and await than a coroutine.
+#[derive(Default)]
+pub(crate) struct Coalescer {
+    batch: Mutex<Option<Arc<Batch>>>,
+}
+
+impl Coalescer {
+    pub(crate) fn submit<'py, T>(
+        &self,
+        py: Python<'py>,
+        data: ContiguousBuffer,
+        seed: i64,
+        hasher: fn(&[u8], i64) -> T,
+    ) -> PyResult<Bound<'py, PyAny>>
+    where
+        T: for<'a> IntoPyObject<'a> + Send + 'static,
+    {
+        let event_loop = py
+            .import(intern!(py, "asyncio"))?
+            .call_method0(intern!(py, "get_running_loop"))?;
+        let future = event_loop.call_method0(intern!(py, "create_future"))?;
+        let mut batch = self.batch.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let request = match batch.as_ref().filter(|batch| batch.event_loop.is(&event_loop)) {
+            Some(batch) => batch.push((data, future.clone().unbind())).err(),
+            None => Some((data, future.clone().unbind())),
+        };
+
+        if let Some(request) = request {
+            let next = Arc::new(Batch {
+                event_loop: event_loop.clone().unbind(),
+                requests: Mutex::new(Some(vec![request])),
+            });
+            let flushed = Arc::clone(&next);
+            let flush =
+                PyCFunction::new_closure(py, None, None, move |args, _| flush(args.py(), &flushed, seed, hasher))?;
+
+            event_loop.call_method1(intern!(py, "call_soon"), (flush,))?;
+            *batch = Some(next);
+        }
+
+        Ok(future)
+    }
+}
+
+fn flush<T>(py: Python<'_>, batch: &Batch, seed: i64, hasher: fn(&[u8], i64) -> T) -> PyResult<()>
+where
+    T: for<'a> IntoPyObject<'a> + Send,
+{
+    let Some(requests) = batch.take() else {
+        return Ok(());
+    };
+
+    let results = py.detach(|| {
+        requests
+            .iter()
+            .map(|(data, _)| hasher(data.as_bytes(), seed))
+            .collect::<Vec<_>>()
+    });
+
+    for ((_, future), hash) in requests.iter().zip(results) {
+        let future = future.bind(py);
+
+        if !future.call_method0(intern!(py, "done"))?.is_truthy()? {
+            future.call_method1(intern!(py, "set_result"), (hash,))?;
+        }
+    }
+
+    Ok(())
+}
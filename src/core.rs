@@ -1,47 +1,199 @@
+use crate::buffer::ContiguousBuffer;
+use crate::buffer::PyBufferExt;
+use crate::buffer::PyBytesLike;
+use crate::coalesce::Coalescer;
+use crate::coroutine::Coroutine;
+use crate::coroutine::StreamCoroutine;
+use crate::executor::block_on;
+use crate::executor::Executor;
+use crate::file;
+use crate::futures;
+use crate::futures::RuntimeExecutor;
+use crate::limiter;
+use crate::offload;
+use crate::offload::OffloadThreshold;
+use crate::progress;
+use crate::progress::Progress;
+use crate::runtime;
+use crate::stream::Stream;
+use crate::timeout;
+use crate::timeout::Timeout;
+use crate::tree;
+
+use pyo3::exceptions::PyAttributeError;
+use pyo3::exceptions::PyDeprecationWarning;
 use pyo3::pyclass;
+use pyo3::pyfunction;
 use pyo3::pymethods;
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyCapsule;
+use pyo3::Bound;
+use pyo3::FromPyObject;
+use pyo3::IntoPyObjectExt;
 use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyErr;
 use pyo3::PyResult;
 use pyo3::Python;
-use tokio::runtime::Handle;
+
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 pyo3::create_exception!(gxhash_py, GxHashAsyncError, pyo3::exceptions::PyException);
+pyo3::create_exception!(gxhash_py, GxHashTimeoutError, GxHashAsyncError);
 
 #[cfg_attr(Py_3_10, pyclass(frozen, immutable_type))]
 #[cfg_attr(not(Py_3_10), pyclass(frozen))]
 pub struct GxHash32 {
     seed: i64,
-    runtime: Handle,
+    offload_threshold: OffloadThreshold,
+    executor: Executor,
+    coalescer: Coalescer,
 }
 
 #[cfg_attr(Py_3_10, pyclass(frozen, immutable_type))]
 #[cfg_attr(not(Py_3_10), pyclass(frozen))]
 pub struct GxHash64 {
     seed: i64,
-    runtime: Handle,
+    offload_threshold: OffloadThreshold,
+    executor: Executor,
+    coalescer: Coalescer,
 }
 
 #[cfg_attr(Py_3_10, pyclass(frozen, immutable_type))]
 #[cfg_attr(not(Py_3_10), pyclass(frozen))]
 pub struct GxHash128 {
     seed: i64,
-    runtime: Handle,
+    offload_threshold: OffloadThreshold,
+    executor: Executor,
+    coalescer: Coalescer,
 }
 
+/// The object formerly exported as `gxhash.core.runtime`, which is kept for compatibility until it is removed
 #[cfg_attr(Py_3_10, pyclass(frozen, immutable_type))]
 #[cfg_attr(not(Py_3_10), pyclass(frozen))]
-struct TokioRuntime {
-    runtime: tokio::runtime::Runtime,
+struct TokioRuntime;
+
+/// Resolves the deprecated module attributes, so that they only warn when they are used
+#[pyfunction]
+#[pyo3(name = "__getattr__")]
+fn module_getattr(py: Python<'_>, name: &str) -> PyResult<TokioRuntime> {
+    if name != "runtime" {
+        return Err(PyAttributeError::new_err(format!(
+            "module 'gxhash.core' has no attribute '{name}'"
+        )));
+    }
+
+    PyErr::warn(
+        py,
+        &py.get_type::<PyDeprecationWarning>(),
+        c"gxhash.core.runtime is deprecated and will be removed in a future release, as the runtime is now started on first use; use configure_runtime and shutdown_runtime instead",
+        1,
+    )?;
+
+    Ok(TokioRuntime)
+}
+
+/// Measures the input size from which asynchronous hashes are offloaded to the runtime by default
+#[pyfunction]
+fn calibrate(py: Python) -> PyResult<usize> {
+    let calibration = offload::Calibration::managed();
+
+    // the first calibration is awaited, so that it cannot overwrite this one once it ends
+    py.detach(|| {
+        calibration.calibrated(&Executor::Managed);
+        calibration.calibrate(&Executor::Managed)
+    })
+}
+
+/// Returns the input size from which asynchronous hashes are offloaded to the runtime by default
+#[pyfunction]
+fn offload_threshold(py: Python) -> usize {
+    py.detach(|| offload::Calibration::managed().calibrated(&Executor::Managed))
+}
+
+/// Sets the number of threads of the runtime, which is started on first use, and limits the offloaded hashes
+#[pyfunction]
+#[pyo3(signature = (*, worker_threads = None, max_in_flight = None, max_in_flight_bytes = None))]
+fn configure_runtime(
+    worker_threads: Option<NonZeroUsize>,
+    max_in_flight: Option<NonZeroUsize>,
+    max_in_flight_bytes: Option<NonZeroUsize>,
+) -> PyResult<()> {
+    runtime::configure(worker_threads, max_in_flight, max_in_flight_bytes)
 }
 
-#[pymethods]
-impl TokioRuntime {
-    #[new]
-    fn new() -> PyResult<Self> {
-        let runtime = tokio::runtime::Builder::new_multi_thread().build()?;
-        Ok(Self { runtime })
+/// Returns the number of asynchronous hashes waiting for a slot before they can be offloaded
+#[pyfunction]
+fn queue_depth() -> usize {
+    limiter::queue_depth()
+}
+
+/// Stops the runtime, which is started again on next use
+#[pyfunction]
+fn shutdown_runtime() {
+    runtime::shutdown();
+}
+
+/// Exports the runtime as an executor capsule, so that other extensions can offload to the same threads
+#[pyfunction]
+fn executor(py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+    crate::executor::export(py)
+}
+
+#[cfg(unix)]
+#[pyfunction]
+fn reset_runtime_after_fork() {
+    runtime::reset_after_fork();
+    crate::coroutine::reset_after_fork();
+    offload::reset_after_fork();
+    timeout::reset_after_fork();
+}
+
+fn partition(items: &[impl PyBufferExt], parts: usize) -> Vec<Range<usize>> {
+    let target_size = items
+        .iter()
+        .map(|item| item.as_bytes().len())
+        .sum::<usize>()
+        .div_ceil(parts);
+    let mut ranges = Vec::with_capacity(parts);
+    let mut start = 0;
+    let mut size = 0;
+
+    for (index, item) in items.iter().enumerate() {
+        size += item.as_bytes().len();
+
+        if size >= target_size {
+            ranges.push(start..index + 1);
+            start = index + 1;
+            size = 0;
+        }
+    }
+
+    if start < items.len() {
+        ranges.push(start..items.len());
     }
+
+    ranges
+}
+
+/// Extracts the arguments of an asynchronous hash, which like those of an `async def` only fail once awaited
+fn extract_async_arguments<T>(data: &Py<PyAny>, timeout: Option<&Py<PyAny>>) -> PyResult<(T, Option<Timeout>)>
+where
+    T: for<'a, 'py> FromPyObject<'a, 'py, Error = PyErr>,
+{
+    Python::attach(|py| {
+        let data = data.extract(py)?;
+        Ok((data, timeout.map(|timeout| timeout.extract(py)).transpose()?))
+    })
+}
+
+/// Extracts the progress arguments of an asynchronous hash, which likewise only fail once awaited
+fn extract_progress(progress: Option<Py<PyAny>>, interval: Option<&Py<PyAny>>) -> PyResult<Option<Progress>> {
+    let interval = Python::attach(|py| interval.map(|interval| interval.extract(py)).transpose())?;
+    Ok(Progress::new(progress, interval))
 }
 
 macro_rules! impl_gxhash_methods {
@@ -49,36 +201,249 @@ macro_rules! impl_gxhash_methods {
         #[pymethods]
         impl $name {
             #[new]
-            fn new(py: Python, seed: i64) -> PyResult<Self> {
-                let runtime = py
-                    .import("gxhash.core")?
-                    .getattr("runtime")?
-                    .extract::<pyo3::PyRef<TokioRuntime>>()?
-                    .runtime
-                    .handle()
-                    .clone();
-
-                Ok(Self { seed, runtime })
+            #[pyo3(signature = (seed, *, offload_threshold = None, executor = None))]
+            fn new(seed: i64, offload_threshold: Option<OffloadThreshold>, executor: Option<Executor>) -> Self {
+                Self {
+                    seed,
+                    offload_threshold: offload_threshold.unwrap_or(OffloadThreshold::Calibrated),
+                    executor: executor.unwrap_or(Executor::Managed),
+                    coalescer: Coalescer::default(),
+                }
             }
 
             #[pyo3(signature = (data, /))]
-            fn hash(&self, data: &[u8]) -> $return_type {
-                $hasher(data, self.seed)
+            fn hash(&self, py: Python, data: PyBytesLike) -> $return_type {
+                let seed = self.seed;
+                let bytes_slice = data.as_bytes();
+
+                if bytes_slice.len() >= offload::DETACH_THRESHOLD {
+                    py.detach(|| $hasher(bytes_slice, seed))
+                } else {
+                    $hasher(bytes_slice, seed)
+                }
+            }
+
+            #[pyo3(signature = (items, /))]
+            fn hash_many(&self, py: Python, items: Vec<Bound<'_, PyAny>>) -> PyResult<Vec<$return_type>> {
+                let seed = self.seed;
+                let items = items
+                    .iter()
+                    .map(|item| item.extract::<PyBytesLike>())
+                    .collect::<PyResult<Vec<_>>>()?;
+
+                Ok(py.detach(|| items.iter().map(|item| $hasher(item.as_bytes(), seed)).collect()))
+            }
+
+            #[pyo3(signature = (data, /, *, chunk_size = None))]
+            fn hash_parallel(
+                &self,
+                py: Python,
+                data: ContiguousBuffer,
+                chunk_size: Option<tree::ChunkSize>,
+            ) -> PyResult<$return_type> {
+                let seed = self.seed;
+                let size = data.as_bytes().len();
+                let chunk_size = chunk_size.map_or(tree::DEFAULT_CHUNK_SIZE, tree::ChunkSize::get);
+                // on a thread of the runtime, the leaves are hashed inline rather than queued behind that thread
+                let parts = if self.executor.is_current() {
+                    1
+                } else {
+                    self.executor.parallelism()?
+                };
+                let ranges = tree::partition(size, chunk_size, parts);
+
+                if let [range] = ranges.as_slice() {
+                    let leaves = py.detach(|| tree::leaves(&data.as_bytes()[range.clone()], chunk_size, seed));
+                    return Ok($hasher(&tree::root(leaves, size, seed), seed));
+                }
+
+                let permit = Arc::new(limiter::acquire_blocking(py, size));
+                let data = Arc::new(data);
+                let tasks = ranges
+                    .into_iter()
+                    .map(|range| {
+                        let data = Arc::clone(&data);
+                        let permit = Arc::clone(&permit);
+                        self.executor.spawn_blocking(move |_| {
+                            let _permit = permit;
+                            tree::leaves(&data.as_bytes()[range], chunk_size, seed)
+                        })
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+
+                drop(permit);
+                let leaves = py.detach(|| {
+                    block_on(async {
+                        let mut leaves = Vec::with_capacity(size.div_ceil(chunk_size));
+
+                        for task in tasks {
+                            leaves.extend(task.join().await?);
+                        }
+
+                        PyResult::Ok(leaves)
+                    })
+                })?;
+
+                // every task has finished with the bytes, so the export is released while attached
+                drop(data);
+                Ok($hasher(&tree::root(leaves, size, seed), seed))
             }
 
             #[pyo3(signature = (data, /))]
-            async fn hash_async(&self, data: Py<pyo3::types::PyBytes>) -> PyResult<$return_type> {
+            fn hash_coalesced<'py>(&self, py: Python<'py>, data: ContiguousBuffer) -> PyResult<Bound<'py, PyAny>> {
+                self.coalescer.submit(py, data, self.seed, $hasher)
+            }
+
+            #[pyo3(signature = (data, /))]
+            fn hash_submit<'py>(&self, py: Python<'py>, data: ContiguousBuffer) -> PyResult<Bound<'py, PyAny>> {
                 let seed = self.seed;
-                let bytes_slice = Python::attach(|py| data.as_bytes(py));
-
-                match bytes_slice.len() < 4 << 20 {
-                    true => Ok($hasher(bytes_slice, seed)),
-                    false => self
-                        .runtime
-                        .spawn_blocking(move || $hasher(Python::attach(|py| data.as_bytes(py)), seed))
-                        .await
-                        .map_err(|e| GxHashAsyncError::new_err(e.to_string())),
+                let size = data.as_bytes().len();
+
+                // on a thread of the runtime, the hash is made inline rather than queued behind that thread
+                if self.executor.is_current() {
+                    return futures::ready(py, py.detach(|| $hasher(data.as_bytes(), seed)));
+                }
+
+                if !self.offload_threshold.offloads(size, &self.executor) {
+                    return futures::ready(py, $hasher(data.as_bytes(), seed));
                 }
+
+                let permit = limiter::acquire_blocking(py, size);
+
+                futures::submit(py, &self.executor, move || {
+                    let _permit = permit;
+                    $hasher(data.as_bytes(), seed)
+                })
+            }
+
+            #[pyo3(signature = (data, /, *, timeout = None, progress = None, progress_interval = None))]
+            fn hash_async(
+                slf: Py<Self>,
+                data: Py<PyAny>,
+                timeout: Option<Py<PyAny>>,
+                progress: Option<Py<PyAny>>,
+                progress_interval: Option<Py<PyAny>>,
+            ) -> Coroutine {
+                Coroutine::new(async move {
+                    let (data, timeout) = extract_async_arguments::<ContiguousBuffer>(&data, timeout.as_ref())?;
+                    let mut progress = extract_progress(progress, progress_interval.as_ref())?;
+                    let this = slf.get();
+                    let seed = this.seed;
+                    let size = data.as_bytes().len();
+
+                    if !this.offload_threshold.offloads(size, &this.executor) {
+                        let hash = $hasher(data.as_bytes(), seed);
+                        progress::finish(progress.as_mut(), size)?;
+                        return Ok(hash);
+                    }
+
+                    // unless cancelled, the worker only borrows the bytes and the export is released here
+                    let data = Arc::new(data);
+                    let pinned = Arc::clone(&data);
+                    let hash = timeout::within(timeout, async {
+                        let permit = limiter::acquire(size).await;
+                        let task = this.executor.spawn_blocking(move |_| {
+                            let _permit = permit;
+                            let hash = $hasher(pinned.as_bytes(), seed);
+                            progress::finish(progress.as_mut(), size).map(|()| hash)
+                        })?;
+
+                        task.join().await?
+                    })
+                    .await;
+
+                    drop(data);
+                    hash
+                })
+            }
+
+            #[pyo3(signature = (path, /, *, timeout = None, progress = None, progress_interval = None))]
+            fn hash_file_async(
+                slf: Py<Self>,
+                path: Py<PyAny>,
+                timeout: Option<Py<PyAny>>,
+                progress: Option<Py<PyAny>>,
+                progress_interval: Option<Py<PyAny>>,
+            ) -> Coroutine {
+                Coroutine::new(async move {
+                    let (path, timeout) = extract_async_arguments::<PathBuf>(&path, timeout.as_ref())?;
+                    let mut progress = extract_progress(progress, progress_interval.as_ref())?;
+                    let this = slf.get();
+                    let seed = this.seed;
+
+                    // the size is taken before the file is opened, so a file that is not regular, such as a pipe,
+                    // only counts towards the number of hashes in flight
+                    let size = std::fs::metadata(&path).map_or(0, |metadata| metadata.len());
+                    let size = usize::try_from(size).unwrap_or(usize::MAX);
+
+                    timeout::within(timeout, async {
+                        let permit = limiter::acquire(size).await;
+                        let task = this.executor.spawn_blocking(move |_| {
+                            let _permit = permit;
+                            let contents = file::read(&path, progress.as_mut())?;
+                            let hash = $hasher(contents.as_bytes(), seed);
+                            progress::finish(progress.as_mut(), contents.as_bytes().len()).map(|()| hash)
+                        })?;
+
+                        task.join().await?
+                    })
+                    .await
+                })
+            }
+
+            #[pyo3(signature = (chunks, /))]
+            fn hash_stream_async(&self, chunks: Py<PyAny>) -> StreamCoroutine {
+                StreamCoroutine::new(chunks, Stream::new(self.seed), |py, stream| {
+                    stream.digest::<$return_type>().into_py_any(py)
+                })
+            }
+
+            #[pyo3(signature = (items, /, *, timeout = None))]
+            fn hash_many_async(slf: Py<Self>, items: Py<PyAny>, timeout: Option<Py<PyAny>>) -> Coroutine {
+                Coroutine::new(async move {
+                    let (items, timeout) = extract_async_arguments::<Vec<ContiguousBuffer>>(&items, timeout.as_ref())?;
+                    let this = slf.get();
+                    let seed = this.seed;
+                    let size = items.iter().map(|item| item.as_bytes().len()).sum();
+
+                    if !this.offload_threshold.offloads(size, &this.executor) {
+                        return Ok(items.iter().map(|item| $hasher(item.as_bytes(), seed)).collect());
+                    }
+
+                    let ranges = partition(&items, this.executor.parallelism()?);
+                    // unless cancelled, the coroutine holds the exports until every task is joined
+                    let items = Arc::new(items);
+
+                    timeout::within(timeout, async {
+                        let permit = Arc::new(limiter::acquire(size).await);
+                        let tasks = ranges
+                            .into_iter()
+                            .map(|range| {
+                                let items = Arc::clone(&items);
+                                let permit = Arc::clone(&permit);
+                                this.executor.spawn_blocking(move |cancellation| {
+                                    let _permit = permit;
+                                    items[range]
+                                        .iter()
+                                        .map_while(|item| {
+                                            (!cancellation.is_cancelled()).then(|| $hasher(item.as_bytes(), seed))
+                                        })
+                                        .collect::<Vec<_>>()
+                                })
+                            })
+                            .collect::<PyResult<Vec<_>>>()?;
+
+                        drop(permit);
+                        let mut hashes = Vec::with_capacity(items.len());
+
+                        for task in tasks {
+                            hashes.extend(task.join().await?);
+                        }
+
+                        Ok(hashes)
+                    })
+                    .await
+                })
             }
         }
     };
@@ -98,22 +463,88 @@ impl_gxhash_methods!(GxHash128, u128, gxhash_core::gxhash128);
 ///
 /// Each class provides methods for hashing byte sequences both synchronously and asynchronously.
 ///
-/// * GxHash32(seed: int)
-/// * hash(data: bytes) -> Uint32
-/// * hash_async(data: bytes) -> Awaitable[Uint32]
+/// * GxHash32(seed: int, *, offload_threshold: int | Literal["always", "never"] | None = None, executor: `CapsuleType` | None = None)
+/// * hash(data: Buffer) -> Uint32
+/// * hash_many(items: Sequence[Buffer]) -> list[Uint32]
+/// * hash_parallel(data: Buffer, *, chunk_size: int = 4 << 20) -> Uint32
+/// * hash_submit(data: Buffer) -> Future[Uint32]
+/// * hash_coalesced(data: Buffer) -> Awaitable[Uint32]
+/// * hash_async(data: Buffer, *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint32]
+/// * hash_file_async(path: str | PathLike[str], *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint32]
+/// * hash_stream_async(chunks: AsyncIterable[Buffer]) -> Awaitable[Uint32]
+/// * hash_many_async(items: Sequence[Buffer], *, timeout: float | None = None) -> Awaitable[list[Uint32]]
+///
+/// * GxHash64(seed: int, *, offload_threshold: int | Literal["always", "never"] | None = None, executor: `CapsuleType` | None = None)
+/// * hash(data: Buffer) -> Uint64
+/// * hash_many(items: Sequence[Buffer]) -> list[Uint64]
+/// * hash_parallel(data: Buffer, *, chunk_size: int = 4 << 20) -> Uint64
+/// * hash_submit(data: Buffer) -> Future[Uint64]
+/// * hash_coalesced(data: Buffer) -> Awaitable[Uint64]
+/// * hash_async(data: Buffer, *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint64]
+/// * hash_file_async(path: str | PathLike[str], *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint64]
+/// * hash_stream_async(chunks: AsyncIterable[Buffer]) -> Awaitable[Uint64]
+/// * hash_many_async(items: Sequence[Buffer], *, timeout: float | None = None) -> Awaitable[list[Uint64]]
+///
+/// * GxHash128(seed: int, *, offload_threshold: int | Literal["always", "never"] | None = None, executor: `CapsuleType` | None = None)
+/// * hash(data: Buffer) -> Uint128
+/// * hash_many(items: Sequence[Buffer]) -> list[Uint128]
+/// * hash_parallel(data: Buffer, *, chunk_size: int = 4 << 20) -> Uint128
+/// * hash_submit(data: Buffer) -> Future[Uint128]
+/// * hash_coalesced(data: Buffer) -> Awaitable[Uint128]
+/// * hash_async(data: Buffer, *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint128]
+/// * hash_file_async(path: str | PathLike[str], *, timeout: float | None = None, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> Awaitable[Uint128]
+/// * hash_stream_async(chunks: AsyncIterable[Buffer]) -> Awaitable[Uint128]
+/// * hash_many_async(items: Sequence[Buffer], *, timeout: float | None = None) -> Awaitable[list[Uint128]]
+///
+/// `hash_parallel` hashes a tree of chunks instead of the input itself, so its output differs from `hash` by design.
+/// Likewise, `hash_stream_async` agrees with the streaming hashlib objects rather than with `hash`.
+///
+/// Unless an `offload_threshold` is given, inputs are offloaded from a threshold measured in the background on first use.
+///
+/// * `calibrate()` -> int
+/// * `offload_threshold()` -> int
+///
+/// Offloaded hashes run on a runtime that is started on first use, and restarted in forked child processes.
+///
+/// * `configure_runtime(*, worker_threads`: int | None = None, `max_in_flight`: int | None = None, `max_in_flight_bytes`: int | None = None) -> None
+/// * `shutdown_runtime()` -> None
+/// * `queue_depth()` -> int
+///
+/// The `runtime` attribute that the runtime was exported as is deprecated, and warns when it is used.
+///
+/// Hashers can instead offload to an `executor` shared by another extension, which never starts the runtime, and the
+/// runtime can be shared in turn.
+///
+/// * `executor()` -> `CapsuleType`
+/// * `RuntimeExecutor()` - a `concurrent.futures.Executor` running callables on the runtime
+///
+/// Cancelled asynchronous hashes skip the work that has not started yet, and those that time out raise `GxHashTimeoutError`.
 ///
-/// * GxHash64(seed: int)
-/// * hash(data: bytes) -> Uint64
-/// * hash_async(data: bytes) -> Awaitable[Uint64]
+/// The `progress` callables are called with the number of bytes processed so far, from the thread processing them.
 ///
-/// * GxHash128(seed: int)
-/// * hash(data: bytes) -> Uint128
-/// * hash_async(data: bytes) -> Awaitable[Uint128]
+/// Asynchronous hashes can be awaited from both asyncio and Trio, whereas `hash_coalesced` requires asyncio.
 ///
 #[pyo3::pymodule(submodule, name = "core", gil_used = false)]
 pub mod core_module {
-    use pyo3::prelude::PyModuleMethods;
+    #[cfg(unix)]
+    use pyo3::types::IntoPyDict;
+    #[cfg(unix)]
+    use pyo3::types::PyAnyMethods;
 
+    #[pymodule_export]
+    use super::calibrate;
+    #[pymodule_export]
+    use super::configure_runtime;
+    #[pymodule_export]
+    use super::executor;
+    #[pymodule_export]
+    use super::module_getattr;
+    #[pymodule_export]
+    use super::offload_threshold;
+    #[pymodule_export]
+    use super::queue_depth;
+    #[pymodule_export]
+    use super::shutdown_runtime;
     #[pymodule_export]
     use super::GxHash128;
     #[pymodule_export]
@@ -122,10 +553,24 @@ pub mod core_module {
     use super::GxHash64;
     #[pymodule_export]
     use super::GxHashAsyncError;
+    #[pymodule_export]
+    use super::GxHashTimeoutError;
+    #[pymodule_export]
+    use super::RuntimeExecutor;
 
     #[pymodule_init]
+    #[cfg_attr(not(unix), allow(unused_variables))]
     fn init(m: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
-        let py = m.py();
-        m.add("runtime", pyo3::Py::new(py, super::TokioRuntime::new()?)?)
+        #[cfg(unix)]
+        {
+            let py = m.py();
+            let after_in_child = pyo3::wrap_pyfunction!(super::reset_runtime_after_fork, m)?;
+
+            py.import("os")?
+                .getattr("register_at_fork")?
+                .call((), Some(&[("after_in_child", after_in_child)].into_py_dict(py)?))?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,539 @@
+use crate::buffer::PyBufferExt;
+use crate::buffer::PyBytesLike;
+use crate::fork::ForkSafe;
+use crate::offload;
+use crate::stream::Stream;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::exceptions::PyStopIteration;
+use pyo3::intern;
+use pyo3::pyclass;
+use pyo3::pyfunction;
+use pyo3::pymethods;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyCFunction;
+use pyo3::types::PyModule;
+use pyo3::wrap_pyfunction;
+use pyo3::Bound;
+use pyo3::IntoPyObject;
+use pyo3::IntoPyObjectExt;
+use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyErr;
+use pyo3::PyResult;
+use pyo3::Python;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::sync::TryLockError;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+
+type BoxedFuture = Pin<Box<dyn Future<Output = PyResult<Py<PyAny>>> + Send>>;
+
+/// What a suspended coroutine is waiting on, which the thread of the `Resumer` resumes once its future is woken
+enum Target {
+    Woken,
+    Asyncio {
+        event_loop: Py<PyAny>,
+        future: Py<PyAny>,
+    },
+    Trio {
+        token: Py<PyAny>,
+        task: Py<PyAny>,
+        armed: Arc<AtomicBool>,
+    },
+}
+
+impl Target {
+    fn resume(self, py: Python<'_>) -> PyResult<()> {
+        match self {
+            Self::Woken => Ok(()),
+            Self::Asyncio { event_loop, future } => {
+                let release_waiter = wrap_pyfunction!(release_waiter, py)?;
+                event_loop
+                    .call_method1(py, intern!(py, "call_soon_threadsafe"), (release_waiter, future))
+                    .map(drop)
+            }
+            Self::Trio { token, task, armed } => {
+                let reschedule = PyCFunction::new_closure(py, None, None, move |args, _| -> PyResult<()> {
+                    // an aborted wait must not be rescheduled, and both callbacks run on the thread of the event loop
+                    if armed.swap(false, Ordering::Relaxed) {
+                        trio_lowlevel(args.py())?.call_method1(intern!(args.py(), "reschedule"), (&task,))?;
+                    }
+
+                    Ok(())
+                })?;
+
+                token
+                    .call_method1(py, intern!(py, "run_sync_soon"), (reschedule,))
+                    .map(drop)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Wakeup(Mutex<Option<Target>>);
+
+impl Wakeup {
+    /// Suspends on `target`, unless the future was already woken while it was polled
+    fn suspend(&self, target: Target) -> bool {
+        let mut current = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(Target::Woken) = current.as_ref() {
+            return false;
+        }
+
+        *current = Some(target);
+        true
+    }
+}
+
+impl Wake for Wakeup {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let target = self
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(Target::Woken);
+
+        if let Some(target @ (Target::Asyncio { .. } | Target::Trio { .. })) = target {
+            RESUMER.get().resume(target);
+        }
+    }
+}
+
+/// The targets of the woken coroutines, which a single thread resumes so that the threads waking them never attach to
+/// the interpreter
+///
+/// The threads of the runtime would otherwise wait for the GIL after every hash rather than start hashing the next one.
+struct Resumer {
+    targets: Mutex<Vec<Target>>,
+    changed: Condvar,
+    thread: OnceLock<bool>,
+}
+
+static RESUMER: ForkSafe<Resumer> = ForkSafe::new(Resumer::new);
+
+impl Resumer {
+    fn new() -> Self {
+        Self {
+            targets: Mutex::new(Vec::new()),
+            changed: Condvar::new(),
+            thread: OnceLock::new(),
+        }
+    }
+
+    fn targets(&self) -> MutexGuard<'_, Vec<Target>> {
+        self.targets.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Hands `target` to the thread resuming the targets, which is started on first use
+    ///
+    /// The target is resumed on the calling thread instead if that thread cannot be started.
+    fn resume(&'static self, target: Target) {
+        let started = self.thread.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("gxhash-wakeup".into())
+                .spawn(|| self.run())
+                .is_ok()
+        });
+
+        if !*started {
+            Python::attach(|py| target.resume(py).ok());
+            return;
+        }
+
+        self.targets().push(target);
+        self.changed.notify_one();
+    }
+
+    /// Resumes the targets in batches, attaching once for all the targets woken in the meantime
+    fn run(&self) {
+        let mut targets = self.targets();
+
+        loop {
+            if targets.is_empty() {
+                targets = self.changed.wait(targets).unwrap_or_else(PoisonError::into_inner);
+                continue;
+            }
+
+            let woken = std::mem::take(&mut *targets);
+            drop(targets);
+
+            // the event loop may have stopped since, in which case nothing awaits the coroutine anymore
+            Python::attach(|py| woken.into_iter().for_each(|target| drop(target.resume(py))));
+            targets = self.targets();
+        }
+    }
+}
+
+/// Forgets the thread resuming the targets inherited from the parent process, as it does not exist in a forked child
+#[cfg(unix)]
+pub(crate) fn reset_after_fork() {
+    RESUMER.reset();
+}
+
+#[pyfunction]
+fn release_waiter(future: &Bound<'_, PyAny>) -> PyResult<()> {
+    let py = future.py();
+
+    if !future.call_method0(intern!(py, "done"))?.is_truthy()? {
+        future.call_method1(intern!(py, "set_result"), (py.None(),))?;
+    }
+
+    Ok(())
+}
+
+fn trio_lowlevel(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+    py.import(intern!(py, "trio.lowlevel"))
+}
+
+/// Returns the awaitable that the current task suspends on until `wakeup` is woken
+///
+/// Trio is looked up first, as a Trio guest run inside asyncio sees both event loops.
+fn suspend<'py>(py: Python<'py>, wakeup: &Wakeup) -> PyResult<Bound<'py, PyAny>> {
+    let modules = py.import(intern!(py, "sys"))?.getattr(intern!(py, "modules"))?;
+
+    if modules.contains(intern!(py, "trio"))? {
+        let lowlevel = trio_lowlevel(py)?;
+
+        if let Ok(task) = lowlevel.call_method0(intern!(py, "current_task")) {
+            let armed = Arc::new(AtomicBool::new(true));
+            let target = Target::Trio {
+                token: lowlevel.call_method0(intern!(py, "current_trio_token"))?.unbind(),
+                task: task.unbind(),
+                armed: Arc::clone(&armed),
+            };
+
+            if !wakeup.suspend(target) {
+                return lowlevel.call_method0(intern!(py, "checkpoint"));
+            }
+
+            let abort = PyCFunction::new_closure(py, None, None, move |args, _| {
+                armed.store(false, Ordering::Relaxed);
+                let abort = trio_lowlevel(args.py())?.getattr(intern!(args.py(), "Abort"))?;
+                abort.getattr(intern!(args.py(), "SUCCEEDED")).map(Bound::unbind)
+            })?;
+
+            return lowlevel.call_method1(intern!(py, "wait_task_rescheduled"), (abort,));
+        }
+    }
+
+    let asyncio = py.import(intern!(py, "asyncio"))?;
+    let event_loop = asyncio.call_method0(intern!(py, "_get_running_loop"))?;
+
+    if event_loop.is_none() {
+        return Err(PyRuntimeError::new_err("no running asyncio or trio event loop"));
+    }
+
+    let future = event_loop.call_method0(intern!(py, "create_future"))?;
+    let target = Target::Asyncio {
+        event_loop: event_loop.unbind(),
+        future: future.clone().unbind(),
+    };
+
+    if !wakeup.suspend(target) {
+        return asyncio.call_method1(intern!(py, "sleep"), (0,));
+    }
+
+    future.call_method0(intern!(py, "__await__"))
+}
+
+struct State {
+    future: Option<BoxedFuture>,
+    awaiting: Option<Py<PyAny>>,
+}
+
+/// A coroutine driving a Rust future, which can be awaited from both asyncio and Trio, and so from `AnyIO`
+///
+/// Whenever the future is pending, the coroutine delegates to an awaitable of the running event loop,
+/// which the waker of the future hands to a single thread resuming it, whichever thread completes it.
+/// Throwing into the coroutine or closing it drops the future, cancelling the tasks it awaits.
+#[cfg_attr(Py_3_10, pyclass(frozen, immutable_type, module = "gxhash.core"))]
+#[cfg_attr(not(Py_3_10), pyclass(frozen, module = "gxhash.core"))]
+pub(crate) struct Coroutine {
+    state: Mutex<State>,
+}
+
+impl Coroutine {
+    pub(crate) fn new<F, T>(future: F) -> Self
+    where
+        F: Future<Output = PyResult<T>> + Send + 'static,
+        T: for<'py> IntoPyObject<'py> + Send,
+    {
+        let future = async move {
+            let output = future.await?;
+            Python::attach(|py| output.into_py_any(py))
+        };
+
+        Self {
+            state: Mutex::new(State {
+                future: Some(Box::pin(future)),
+                awaiting: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> PyResult<MutexGuard<'_, State>> {
+        lock(&self.state)
+    }
+}
+
+fn lock<T>(state: &Mutex<T>) -> PyResult<MutexGuard<'_, T>> {
+    match state.try_lock() {
+        Ok(state) => Ok(state),
+        Err(TryLockError::Poisoned(state)) => Ok(state.into_inner()),
+        Err(TryLockError::WouldBlock) => Err(PyRuntimeError::new_err("coroutine already executing")),
+    }
+}
+
+#[pymethods]
+impl Coroutine {
+    fn send(&self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let mut state = self.state()?;
+
+        if let Some(awaiting) = state.awaiting.take() {
+            match awaiting.call_method1(py, intern!(py, "send"), (value,)) {
+                Ok(yielded) => {
+                    state.awaiting = Some(awaiting);
+                    return Ok(yielded);
+                }
+                Err(err) if err.is_instance_of::<PyStopIteration>(py) => {}
+                Err(err) => {
+                    state.future = None;
+                    return Err(err);
+                }
+            }
+        }
+
+        let Some(future) = state.future.as_mut() else {
+            return Err(PyRuntimeError::new_err("cannot reuse already awaited coroutine"));
+        };
+
+        let wakeup = Arc::new(Wakeup::default());
+        let poll = future
+            .as_mut()
+            .poll(&mut Context::from_waker(&Waker::from(Arc::clone(&wakeup))));
+
+        if let Poll::Ready(output) = poll {
+            state.future = None;
+            return Err(PyStopIteration::new_err((output?,)));
+        }
+
+        let awaiting = match suspend(py, &wakeup) {
+            Ok(awaiting) => awaiting,
+            Err(err) => {
+                state.future = None;
+                return Err(err);
+            }
+        };
+
+        let yielded = awaiting.call_method1(intern!(py, "send"), (py.None(),))?;
+        state.awaiting = Some(awaiting.unbind());
+        Ok(yielded.unbind())
+    }
+
+    fn throw(&self, py: Python<'_>, exc: Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        self.close(py)?;
+        Err(PyErr::from_value(exc))
+    }
+
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        let mut state = self.state()?;
+        state.future = None;
+
+        if let Some(awaiting) = state.awaiting.take() {
+            awaiting.call_method0(py, intern!(py, "close"))?;
+        }
+
+        Ok(())
+    }
+
+    fn __await__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.send(py, &py.None().into_bound(py))
+    }
+}
+
+type Digest = fn(Python<'_>, &Stream) -> PyResult<Py<PyAny>>;
+
+struct StreamState {
+    chunks: Py<PyAny>,
+    iterator: Option<Py<PyAny>>,
+    awaiting: Option<Py<PyAny>>,
+    stream: Option<Stream>,
+    digest: Digest,
+}
+
+impl StreamState {
+    /// Starts awaiting the next chunk of the asynchronous iterator
+    fn next_chunk(&mut self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let iterator = match &self.iterator {
+            Some(iterator) => iterator.bind(py).clone(),
+            None => self
+                .iterator
+                .insert(self.chunks.call_method0(py, intern!(py, "__aiter__"))?)
+                .bind(py)
+                .clone(),
+        };
+
+        let awaiting = iterator
+            .call_method0(intern!(py, "__anext__"))?
+            .call_method0(intern!(py, "__await__"))?;
+        self.awaiting = Some(awaiting.clone().unbind());
+        awaiting
+            .call_method1(intern!(py, "send"), (py.None(),))
+            .map(Bound::unbind)
+    }
+
+    /// Hashes every chunk that `resumed` and the following `__anext__` return, until one of them suspends
+    fn advance(&mut self, py: Python<'_>, mut resumed: PyResult<Py<PyAny>>) -> PyResult<Py<PyAny>> {
+        loop {
+            let err = match resumed {
+                Ok(yielded) => return Ok(yielded),
+                Err(err) => err,
+            };
+
+            self.awaiting = None;
+
+            if err.is_instance_of::<PyStopAsyncIteration>(py) {
+                let Some(stream) = self.stream.as_ref() else {
+                    return Err(PyRuntimeError::new_err("cannot reuse already awaited coroutine"));
+                };
+
+                return Err(PyStopIteration::new_err(((self.digest)(py, stream)?,)));
+            }
+
+            if !err.is_instance_of::<PyStopIteration>(py) {
+                return Err(err);
+            }
+
+            let value = err.value(py).getattr(intern!(py, "value"))?;
+            let chunk = value.extract::<PyBytesLike>()?;
+            let Some(stream) = self.stream.as_mut() else {
+                return Err(PyRuntimeError::new_err("cannot reuse already awaited coroutine"));
+            };
+
+            if chunk.as_bytes().len() < offload::DETACH_THRESHOLD {
+                stream.update(chunk.as_bytes());
+            } else {
+                py.detach(|| stream.update(chunk.as_bytes()));
+            }
+
+            resumed = self.next_chunk(py);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.iterator = None;
+        self.awaiting = None;
+        self.stream = None;
+    }
+
+    /// Ends the coroutine once it returned or raised, as it cannot be resumed anymore
+    fn settle(&mut self, output: PyResult<Py<PyAny>>) -> PyResult<Py<PyAny>> {
+        if output.is_err() {
+            self.finish();
+        }
+
+        output
+    }
+}
+
+/// A coroutine hashing the chunks of an asynchronous iterator as they arrive, without buffering them
+///
+/// Each `__anext__` is delegated to as with `yield from`, so the coroutine can be awaited from any event loop
+/// that the iterator supports. The chunks are hashed on the thread of the event loop as they are returned.
+#[cfg_attr(Py_3_10, pyclass(frozen, immutable_type, module = "gxhash.core"))]
+#[cfg_attr(not(Py_3_10), pyclass(frozen, module = "gxhash.core"))]
+pub(crate) struct StreamCoroutine {
+    state: Mutex<StreamState>,
+}
+
+impl StreamCoroutine {
+    pub(crate) fn new(chunks: Py<PyAny>, stream: Stream, digest: Digest) -> Self {
+        Self {
+            state: Mutex::new(StreamState {
+                chunks,
+                iterator: None,
+                awaiting: None,
+                stream: Some(stream),
+                digest,
+            }),
+        }
+    }
+}
+
+#[pymethods]
+impl StreamCoroutine {
+    fn send(&self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let mut state = lock(&self.state)?;
+
+        if state.stream.is_none() {
+            return Err(PyRuntimeError::new_err("cannot reuse already awaited coroutine"));
+        }
+
+        let resumed = match state.awaiting.as_ref() {
+            Some(awaiting) => awaiting.call_method1(py, intern!(py, "send"), (value,)),
+            None => state.next_chunk(py),
+        };
+
+        let output = state.advance(py, resumed);
+        state.settle(output)
+    }
+
+    fn throw(&self, py: Python<'_>, exc: Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let mut state = lock(&self.state)?;
+        let resumed = match state.awaiting.as_ref() {
+            Some(awaiting) if awaiting.bind(py).hasattr(intern!(py, "throw"))? => {
+                awaiting.call_method1(py, intern!(py, "throw"), (exc,))
+            }
+            _ => Err(PyErr::from_value(exc)),
+        };
+
+        let output = state.advance(py, resumed);
+        state.settle(output)
+    }
+
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        let mut state = lock(&self.state)?;
+        let awaiting = state.awaiting.take();
+        state.finish();
+
+        if let Some(awaiting) = awaiting {
+            if awaiting.bind(py).hasattr(intern!(py, "close"))? {
+                awaiting.call_method0(py, intern!(py, "close"))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn __await__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.send(py, &py.None().into_bound(py))
+    }
+}
@@ -0,0 +1,300 @@
+use crate::core::GxHashAsyncError;
+use crate::offload::Calibration;
+use crate::runtime;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyCapsule;
+use pyo3::types::PyCapsuleMethods;
+use pyo3::Borrowed;
+use pyo3::Bound;
+use pyo3::FromPyObject;
+use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyErr;
+use pyo3::PyResult;
+use pyo3::Python;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+use std::thread::Thread;
+
+pub const EXECUTOR_CAPSULE_NAME: &CStr = c"gxhash.core.executor";
+pub const EXECUTOR_ABI_VERSION: u32 = 1;
+
+/// A task handed to an executor
+///
+/// It must be called exactly once with its `argument`, and with `run` set to false if it is dropped without running.
+pub type GxHashTask = unsafe extern "C" fn(argument: *mut c_void, run: bool);
+
+/// The C ABI of an executor that `GxHash*` can offload hashes to
+///
+/// Executors are exchanged as a `PyCapsule` named `gxhash.core.executor`, which keeps the `context` alive.
+/// Both functions must be callable from any thread.
+#[repr(C)]
+pub struct GxHashExecutor {
+    /// must be `EXECUTOR_ABI_VERSION`
+    pub version: u32,
+    /// passed back to both functions
+    pub context: *mut c_void,
+    /// returns the number of threads that tasks run on
+    pub parallelism: unsafe extern "C" fn(context: *mut c_void) -> usize,
+    /// runs the task on a thread that may block, returning zero if it was accepted and leaving it untouched otherwise
+    pub spawn_blocking: unsafe extern "C" fn(context: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int,
+}
+
+unsafe impl Send for GxHashExecutor {}
+
+type BoxedTask = Box<dyn FnOnce() + Send>;
+
+unsafe extern "C" fn run_boxed_task(argument: *mut c_void, run: bool) {
+    let task = unsafe { Box::from_raw(argument.cast::<BoxedTask>()) };
+
+    if run {
+        task();
+    }
+}
+
+fn foreign_executor<'a>(capsule: &'a Bound<'_, PyCapsule>) -> PyResult<&'a GxHashExecutor> {
+    let executor = unsafe {
+        capsule
+            .pointer_checked(Some(EXECUTOR_CAPSULE_NAME))?
+            .cast::<GxHashExecutor>()
+            .as_ref()
+    };
+
+    if executor.version != EXECUTOR_ABI_VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported executor ABI version {}, expected {EXECUTOR_ABI_VERSION}",
+            executor.version
+        )));
+    }
+
+    Ok(executor)
+}
+
+/// An executor exported by another extension, with the offload threshold measured against it
+pub(crate) struct Foreign {
+    capsule: Py<PyCapsule>,
+    calibration: Calibration,
+}
+
+#[derive(Clone)]
+pub(crate) enum Executor {
+    Managed,
+    Foreign(Arc<Foreign>),
+}
+
+/// Set once nothing awaits a task anymore, so that the task can stop before finishing its work
+#[derive(Clone, Default)]
+pub(crate) struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+enum Completion<R> {
+    Managed(JoinHandle<Option<R>>),
+    Foreign(oneshot::Receiver<Option<R>>),
+}
+
+/// A task offloaded to an executor, which is cancelled when dropped before completing
+pub(crate) struct BlockingTask<R> {
+    completion: Completion<R>,
+    cancellation: Cancellation,
+}
+
+impl<R> BlockingTask<R> {
+    pub(crate) async fn join(mut self) -> PyResult<R> {
+        let output = match &mut self.completion {
+            Completion::Managed(handle) => handle.await.map_err(|e| GxHashAsyncError::new_err(e.to_string()))?,
+            Completion::Foreign(receiver) => receiver
+                .await
+                .map_err(|_| GxHashAsyncError::new_err("the executor dropped the task without running it"))?,
+        };
+
+        output.ok_or_else(|| GxHashAsyncError::new_err("the task was cancelled"))
+    }
+
+    /// Lets the task run to completion without anything awaiting it
+    pub(crate) fn detach(mut self) {
+        self.cancellation = Cancellation::default();
+    }
+}
+
+impl<R> Drop for BlockingTask<R> {
+    fn drop(&mut self) {
+        self.cancellation.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Executor {
+    /// Returns the offload threshold measured against this executor, as the cost of a round trip depends on it
+    pub(crate) fn calibration(&self) -> &Calibration {
+        match self {
+            Self::Managed => Calibration::managed(),
+            Self::Foreign(foreign) => &foreign.calibration,
+        }
+    }
+
+    /// Returns whether the current thread belongs to the managed runtime, which must not block on its own tasks
+    ///
+    /// Blocking there could wait on tasks queued behind the blocking thread itself, and so never return.
+    pub(crate) fn is_current(&self) -> bool {
+        matches!(self, Self::Managed) && Handle::try_current().is_ok()
+    }
+
+    pub(crate) fn parallelism(&self) -> PyResult<usize> {
+        match self {
+            Self::Managed => Ok(runtime::handle()?.metrics().num_workers()),
+            Self::Foreign(foreign) => Python::attach(|py| {
+                let executor = foreign_executor(foreign.capsule.bind(py))?;
+                Ok(unsafe { (executor.parallelism)(executor.context) }.max(1))
+            }),
+        }
+    }
+
+    /// Offloads `function`, which is skipped if the task is cancelled before it starts
+    ///
+    /// Once started, `function` can poll its `Cancellation` to stop early.
+    pub(crate) fn spawn_blocking<F, R>(&self, function: F) -> PyResult<BlockingTask<R>>
+    where
+        F: FnOnce(&Cancellation) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let cancellation = Cancellation::default();
+        let token = cancellation.clone();
+        let function = move || (!token.is_cancelled()).then(|| function(&token));
+
+        let Self::Foreign(foreign) = self else {
+            return Ok(BlockingTask {
+                completion: Completion::Managed(runtime::handle()?.spawn_blocking(function)),
+                cancellation,
+            });
+        };
+
+        Python::attach(|py| {
+            let executor = foreign_executor(foreign.capsule.bind(py))?;
+            let (sender, receiver) = oneshot::channel();
+            let task: BoxedTask = Box::new(move || {
+                let _ = sender.send(function());
+            });
+            let argument = Box::into_raw(Box::new(task)).cast::<c_void>();
+
+            match unsafe { (executor.spawn_blocking)(executor.context, run_boxed_task, argument) } {
+                0 => Ok(BlockingTask {
+                    completion: Completion::Foreign(receiver),
+                    cancellation,
+                }),
+                status => {
+                    unsafe { run_boxed_task(argument, false) };
+                    Err(GxHashAsyncError::new_err(format!(
+                        "the executor rejected the task with status {status}"
+                    )))
+                }
+            }
+        })
+    }
+}
+
+impl FromPyObject<'_, '_> for Executor {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        let capsule = obj.cast::<PyCapsule>()?;
+        foreign_executor(&capsule)?;
+
+        Ok(Self::Foreign(Arc::new(Foreign {
+            capsule: capsule.to_owned().unbind(),
+            calibration: Calibration::new(),
+        })))
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Blocks the calling thread until `future` is ready, without a runtime to drive it
+///
+/// This lets the synchronous API wait on tasks and slots without starting the managed runtime, which a foreign
+/// executor would otherwise start for nothing.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        std::thread::park();
+    }
+}
+
+struct ManagedTask {
+    task: GxHashTask,
+    argument: *mut c_void,
+}
+
+unsafe impl Send for ManagedTask {}
+
+impl ManagedTask {
+    fn run(self) {
+        let this = ManuallyDrop::new(self);
+        unsafe { (this.task)(this.argument, true) };
+    }
+}
+
+impl Drop for ManagedTask {
+    fn drop(&mut self) {
+        unsafe { (self.task)(self.argument, false) };
+    }
+}
+
+unsafe extern "C" fn managed_parallelism(_: *mut c_void) -> usize {
+    runtime::handle().map_or(1, |runtime| runtime.metrics().num_workers())
+}
+
+unsafe extern "C" fn managed_spawn_blocking(_: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int {
+    let Ok(runtime) = runtime::handle() else {
+        return -1;
+    };
+
+    let task = ManagedTask { task, argument };
+    drop(runtime.spawn_blocking(move || task.run()));
+    0
+}
+
+/// Exports the managed runtime as an executor, so that other extensions can share its threads
+///
+/// The executor always offloads to the current runtime, so it outlives `shutdown_runtime` and forks.
+pub(crate) fn export(py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+    let executor = GxHashExecutor {
+        version: EXECUTOR_ABI_VERSION,
+        context: std::ptr::null_mut(),
+        parallelism: managed_parallelism,
+        spawn_blocking: managed_spawn_blocking,
+    };
+
+    PyCapsule::new_with_value(py, executor, EXECUTOR_CAPSULE_NAME)
+}
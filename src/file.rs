@@ -0,0 +1,201 @@
+use crate::progress::Progress;
+
+use pyo3::PyResult;
+
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+
+#[cfg(unix)]
+use std::ffi::c_void;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(windows)]
+use std::os::windows::fs::OpenOptionsExt;
+
+#[cfg(windows)]
+const FILE_FLAG_DELETE_ON_CLOSE: u32 = 0x0400_0000;
+
+/// Files from this size are mapped into memory instead of being read, as the mapping is then cheaper than the copy
+#[cfg(unix)]
+const MAP_THRESHOLD: usize = 1 << 20;
+
+/// A read-only mapping of a whole file, which stays valid after the file is closed
+///
+/// As with any mapping, truncating the file while it is mapped makes reading its missing pages raise `SIGBUS`.
+#[cfg(unix)]
+pub(crate) struct Mapping {
+    pointer: *mut c_void,
+    length: usize,
+}
+
+#[cfg(unix)]
+unsafe impl Send for Mapping {}
+
+#[cfg(unix)]
+unsafe impl Sync for Mapping {}
+
+#[cfg(unix)]
+impl Mapping {
+    fn new(file: &File, length: usize) -> io::Result<Self> {
+        let pointer = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                length,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if pointer == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { pointer, length })
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer.cast(), self.length) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.pointer, self.length) };
+    }
+}
+
+/// The contents of a file, which are never copied into a Python object
+pub(crate) enum Contents {
+    #[cfg(unix)]
+    Mapped(Mapping),
+    Read(Vec<u8>),
+}
+
+impl Contents {
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            #[cfg(unix)]
+            Self::Mapped(mapping) => mapping.as_bytes(),
+            Self::Read(bytes) => bytes,
+        }
+    }
+}
+
+/// The largest read made at once while reporting progress, so that reports are not delayed by a single large read
+const READ_SIZE: usize = 1 << 20;
+
+/// Reads the whole file at `path`, mapping large regular files into memory unless the progress is reported
+///
+/// The pages of a mapping are only read while they are hashed, which the one-shot hash cannot report, so the file is
+/// read in windows instead when the progress is reported. Files that cannot be mapped, such as pipes or any file on
+/// platforms without `mmap`, are read to their end as well, as the one-shot hash needs the whole contents at once.
+pub(crate) fn read(path: &Path, progress: Option<&mut Progress>) -> PyResult<Contents> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let length = usize::try_from(metadata.len()).map_err(io::Error::other)?;
+
+    #[cfg(unix)]
+    if metadata.is_file() && length >= MAP_THRESHOLD && progress.is_none() {
+        return Ok(Contents::Mapped(Mapping::new(&file, length)?));
+    }
+
+    let mut contents = Vec::with_capacity(length);
+
+    let Some(progress) = progress else {
+        file.read_to_end(&mut contents)?;
+        return Ok(Contents::Read(contents));
+    };
+
+    let read_size = progress.interval().min(READ_SIZE) as u64;
+
+    while (&mut file).take(read_size).read_to_end(&mut contents)? > 0 {
+        progress.update(contents.len())?;
+    }
+
+    Ok(Contents::Read(contents))
+}
+
+/// Fills `buffer` from `reader`, reporting the bytes read so far to `progress`
+pub(crate) fn read_exact(mut reader: impl Read, buffer: &mut [u8], progress: Option<&mut Progress>) -> PyResult<()> {
+    let Some(progress) = progress else {
+        return Ok(reader.read_exact(buffer)?);
+    };
+
+    let mut filled = 0;
+
+    for chunk in buffer.chunks_mut(progress.interval().min(READ_SIZE)) {
+        reader.read_exact(chunk)?;
+        filled += chunk.len();
+        progress.update(filled)?;
+    }
+
+    Ok(())
+}
+
+/// Creates an anonymous temporary file, which is deleted once it is closed
+///
+/// On Linux, the file never has a name. Elsewhere, it is created under a random name that cannot be predicted by other
+/// users, is only accessible to its owner on Unix, and is removed as soon as it is opened.
+pub(crate) fn temporary() -> io::Result<File> {
+    let directory = std::env::temp_dir();
+
+    #[cfg(target_os = "linux")]
+    if let Ok(file) = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .mode(0o600)
+        .custom_flags(libc::O_TMPFILE)
+        .open(&directory)
+    {
+        return Ok(file);
+    }
+
+    loop {
+        let path = directory.join(format!("gxhash-{:016x}", RandomState::new().build_hasher().finish()));
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        #[cfg(windows)]
+        options.custom_flags(FILE_FLAG_DELETE_ON_CLOSE);
+
+        let file = match options.open(&path) {
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            file => file?,
+        };
+
+        #[cfg(unix)]
+        std::fs::remove_file(&path)?;
+
+        return Ok(file);
+    }
+}
+
+/// Maps the first `length` bytes of `file` into memory, or reads them on platforms without `mmap`
+pub(crate) fn map(file: &mut File, length: usize) -> io::Result<Contents> {
+    #[cfg(unix)]
+    if length > 0 {
+        return Mapping::new(file, length).map(Contents::Mapped);
+    }
+
+    let mut contents = Vec::with_capacity(length);
+    file.seek(SeekFrom::Start(0))?;
+    (&mut *file).take(length as u64).read_to_end(&mut contents)?;
+    file.seek(SeekFrom::End(0))?;
+    Ok(Contents::Read(contents))
+}
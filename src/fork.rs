@@ -0,0 +1,51 @@
+use std::ptr;
+use std::sync::atomic::AtomicPtr;
+use std::sync::atomic::Ordering;
+
+/// A global that is allocated on first use, and that forked children replace instead of locking
+///
+/// Another thread of the parent may have been holding the global while forking, and as that thread does not exist in
+/// the child, locking the global there could deadlock. The replaced values are leaked rather than dropped, since they
+/// may refer to resources of the parent, such as the threads of its runtime.
+pub(crate) struct ForkSafe<T> {
+    value: AtomicPtr<T>,
+    init: fn() -> T,
+}
+
+impl<T: Send + Sync> ForkSafe<T> {
+    pub(crate) const fn new(init: fn() -> T) -> Self {
+        Self {
+            value: AtomicPtr::new(ptr::null_mut()),
+            init,
+        }
+    }
+
+    pub(crate) fn get(&self) -> &T {
+        let mut value = self.value.load(Ordering::Acquire);
+
+        if value.is_null() {
+            let init = Box::into_raw(Box::new((self.init)()));
+
+            value = match self
+                .value
+                .compare_exchange(ptr::null_mut(), init, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => init,
+                Err(current) => {
+                    drop(unsafe { Box::from_raw(init) });
+                    current
+                }
+            };
+        }
+
+        // the values are never freed, so they outlive any reference to them
+        unsafe { &*value }
+    }
+
+    /// Replaces the value with a new one without locking the previous one, which is leaked
+    #[cfg(unix)]
+    pub(crate) fn reset(&self) {
+        self.value
+            .store(Box::into_raw(Box::new((self.init)())), Ordering::Release);
+    }
+}
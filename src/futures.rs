@@ -0,0 +1,256 @@
+use crate::core::GxHashAsyncError;
+use crate::executor::Executor;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::intern;
+use pyo3::pyclass;
+use pyo3::pymethods;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::types::PyTuple;
+use pyo3::types::PyTupleMethods;
+use pyo3::Bound;
+use pyo3::IntoPyObject;
+use pyo3::IntoPyObjectExt;
+use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyResult;
+use pyo3::Python;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+/// Resolves a `concurrent.futures.Future` from the thread that runs its task
+///
+/// The future fails if the task is dropped without running, so that nothing waits on it forever.
+struct Resolver(Option<Py<PyAny>>);
+
+impl Resolver {
+    /// Marks the future as running, unless it was cancelled while the task was queued
+    fn start(&mut self) -> bool {
+        let started = Python::attach(|py| {
+            self.0.as_ref().is_some_and(|future| {
+                future
+                    .call_method0(py, intern!(py, "set_running_or_notify_cancel"))
+                    .and_then(|running| running.is_truthy(py))
+                    .unwrap_or(false)
+            })
+        });
+
+        if !started {
+            self.0 = None;
+        }
+
+        started
+    }
+
+    fn resolve(mut self, output: impl for<'py> FnOnce(Python<'py>) -> PyResult<Bound<'py, PyAny>>) {
+        if let Some(future) = self.0.take() {
+            Python::attach(|py| resolve(py, &future, output(py)));
+        }
+    }
+}
+
+impl Drop for Resolver {
+    fn drop(&mut self) {
+        if let Some(future) = self.0.take() {
+            Python::try_attach(|py| {
+                let error = GxHashAsyncError::new_err("the executor dropped the task without running it");
+                resolve(py, &future, Err(error));
+            });
+        }
+    }
+}
+
+fn resolve(py: Python<'_>, future: &Py<PyAny>, output: PyResult<Bound<'_, PyAny>>) {
+    let resolved = match output {
+        Ok(result) => future.call_method1(py, intern!(py, "set_result"), (result,)),
+        Err(err) => future.call_method1(py, intern!(py, "set_exception"), (err.into_value(py),)),
+    };
+
+    if let Err(err) = resolved {
+        err.write_unraisable(py, Some(future.bind(py)));
+    }
+}
+
+fn new_future(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    py.import(intern!(py, "concurrent.futures"))?
+        .call_method0(intern!(py, "Future"))
+}
+
+/// Returns a `concurrent.futures.Future` that is already resolved with `output`
+pub(crate) fn ready<'py>(py: Python<'py>, output: impl IntoPyObject<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let future = new_future(py)?;
+    future.call_method1(intern!(py, "set_result"), (output.into_bound_py_any(py)?,))?;
+    Ok(future)
+}
+
+/// Runs `function` on `executor`, returning a `concurrent.futures.Future` of its output
+///
+/// Cancelling the future before the task starts skips `function`, and its done callbacks run on the worker thread.
+pub(crate) fn submit<'py, F, T>(py: Python<'py>, executor: &Executor, function: F) -> PyResult<Bound<'py, PyAny>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: for<'a> IntoPyObject<'a> + Send + 'static,
+{
+    let future = new_future(py)?;
+    let mut resolver = Resolver(Some(future.clone().unbind()));
+
+    executor
+        .spawn_blocking(move |_| {
+            if resolver.start() {
+                let output = function();
+                resolver.resolve(|py| output.into_bound_py_any(py));
+            }
+        })?
+        .detach();
+
+    Ok(future)
+}
+
+type Pending = Arc<Mutex<HashMap<u64, Py<PyAny>>>>;
+
+struct Submissions {
+    pending: Pending,
+    next: u64,
+    shutdown: bool,
+}
+
+/// A `concurrent.futures.Executor` running Python callables on the threads of the runtime
+///
+/// Callables run with the interpreter attached, so they only run in parallel with each other when they detach from it,
+/// as `hash` does for large inputs. Shutting the executor down does not stop the runtime, which other hashers share.
+#[cfg_attr(Py_3_10, pyclass(frozen, immutable_type, module = "gxhash.core"))]
+#[cfg_attr(not(Py_3_10), pyclass(frozen, module = "gxhash.core"))]
+pub struct RuntimeExecutor {
+    submissions: Mutex<Submissions>,
+}
+
+impl RuntimeExecutor {
+    fn submissions(&self) -> MutexGuard<'_, Submissions> {
+        self.submissions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[pymethods]
+impl RuntimeExecutor {
+    #[new]
+    fn new() -> Self {
+        Self {
+            submissions: Mutex::new(Submissions {
+                pending: Pending::default(),
+                next: 0,
+                shutdown: false,
+            }),
+        }
+    }
+
+    #[pyo3(signature = (r#fn, /, *args, **kwargs))]
+    fn submit<'py>(
+        &self,
+        py: Python<'py>,
+        r#fn: Py<PyAny>,
+        args: Py<PyTuple>,
+        kwargs: Option<Py<PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut submissions = self.submissions();
+
+        if submissions.shutdown {
+            return Err(PyRuntimeError::new_err("cannot schedule new futures after shutdown"));
+        }
+
+        let id = submissions.next;
+        let pending = Arc::clone(&submissions.pending);
+        let future = new_future(py)?;
+        let mut resolver = Resolver(Some(future.clone().unbind()));
+
+        submissions.next += 1;
+        pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, future.clone().unbind());
+
+        let task = Executor::Managed.spawn_blocking(move |_| {
+            if resolver.start() {
+                resolver.resolve(|py| {
+                    let kwargs = kwargs.as_ref().map(|kwargs| kwargs.bind(py));
+                    r#fn.bind(py).call(args.bind(py), kwargs)
+                });
+            }
+
+            Python::attach(|_| pending.lock().unwrap_or_else(PoisonError::into_inner).remove(&id));
+        });
+
+        match task {
+            Ok(task) => task.detach(),
+            Err(err) => {
+                submissions
+                    .pending
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remove(&id);
+                return Err(err);
+            }
+        }
+
+        Ok(future)
+    }
+
+    #[pyo3(signature = (r#fn, /, *iterables, **kwargs))]
+    fn map<'py>(
+        slf: &Bound<'py, Self>,
+        r#fn: &Bound<'py, PyAny>,
+        iterables: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let map = py
+            .import(intern!(py, "concurrent.futures"))?
+            .getattr(intern!(py, "Executor"))?
+            .getattr(intern!(py, "map"))?;
+        let mut args = vec![slf.as_any().clone(), r#fn.clone()];
+        args.extend(iterables.iter());
+
+        map.call(PyTuple::new(py, args)?, kwargs)
+    }
+
+    #[pyo3(signature = (wait = true, *, cancel_futures = false))]
+    fn shutdown(&self, py: Python<'_>, wait: bool, cancel_futures: bool) -> PyResult<()> {
+        let futures = {
+            let mut submissions = self.submissions();
+            submissions.shutdown = true;
+            let pending = submissions.pending.lock().unwrap_or_else(PoisonError::into_inner);
+            pending.values().map(|future| future.clone_ref(py)).collect::<Vec<_>>()
+        };
+
+        if cancel_futures {
+            for future in &futures {
+                future.call_method0(py, intern!(py, "cancel"))?;
+            }
+        }
+
+        if wait {
+            py.import(intern!(py, "concurrent.futures"))?
+                .call_method1(intern!(py, "wait"), (futures,))?;
+        }
+
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        self.shutdown(py, true, false)
+    }
+}
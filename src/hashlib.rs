@@ -1,22 +1,36 @@
+use crate::accumulator::Accumulator;
+use crate::buffer::ContiguousBuffer;
 use crate::buffer::PyBufferExt;
+use crate::buffer::PyBytesLike;
+use crate::file;
+use crate::progress;
+use crate::progress::Progress;
+use crate::stream::Stream;
 
-use pyo3::buffer::PyBuffer;
 use pyo3::intern;
 use pyo3::pyclass;
 use pyo3::pyfunction;
 use pyo3::pymethods;
+use pyo3::sync::MutexExt;
 use pyo3::types::PyAnyMethods;
 use pyo3::types::PyDict;
+use pyo3::types::PyString;
+use pyo3::types::PyStringMethods;
+use pyo3::types::PyTuple;
 use pyo3::Bound;
 use pyo3::IntoPyObjectExt;
+use pyo3::Py;
 use pyo3::PyAny;
 use pyo3::PyResult;
 use pyo3::Python;
 
 use std::fs::File;
-use std::io::Read;
 use std::io::Seek;
 use std::mem::ManuallyDrop;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
 
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64;
@@ -182,29 +196,69 @@ impl HexDigest for u128 {
     }
 }
 
-#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", immutable_type))]
-#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib"))]
+enum State {
+    Buffered(Accumulator),
+    Streaming(Stream),
+}
+
+/// The input of a HASH object and its cached digest, which are locked together so that threads can share the object
+struct Hash<T> {
+    state: State,
+    cached: Option<T>,
+}
+
+#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", frozen, immutable_type))]
+#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib", frozen))]
 pub(crate) struct GxHashLib32 {
     seed: i64,
-    buffer: PyBuffer<u8>,
+    hash: Mutex<Hash<u32>>,
 }
 
-#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", immutable_type))]
-#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib"))]
+#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", frozen, immutable_type))]
+#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib", frozen))]
 pub(crate) struct GxHashLib64 {
     seed: i64,
-    buffer: PyBuffer<u8>,
+    hash: Mutex<Hash<u64>>,
 }
 
-#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", immutable_type))]
-#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib"))]
+#[cfg_attr(Py_3_10, pyclass(name = "HASH", module = "_hashlib", frozen, immutable_type))]
+#[cfg_attr(not(Py_3_10), pyclass(name = "HASH", module = "_hashlib", frozen))]
 pub(crate) struct GxHashLib128 {
     seed: i64,
-    buffer: PyBuffer<u8>,
+    hash: Mutex<Hash<u128>>,
 }
 
 macro_rules! impl_hashlib {
-    ($name:ident, $function_name:ident, $digest_size:expr, $hasher:path) => {
+    ($name:ident, $function_name:ident, $digest_size:expr, $digest_type:ty, $hasher:path) => {
+        impl $name {
+            fn new(seed: i64, state: State, cached: Option<$digest_type>) -> Self {
+                Self {
+                    seed,
+                    hash: Mutex::new(Hash { state, cached }),
+                }
+            }
+
+            fn lock(&self, py: Python<'_>) -> MutexGuard<'_, Hash<$digest_type>> {
+                self.hash.lock_py_attached(py).unwrap_or_else(PoisonError::into_inner)
+            }
+
+            /// Hashes the input, which is only hashed again once it is updated
+            fn hash(&self, py: Python<'_>) -> PyResult<$digest_type> {
+                let mut hash = self.lock(py);
+
+                if let Some(cached) = hash.cached {
+                    return Ok(cached);
+                }
+
+                let digest = match &mut hash.state {
+                    State::Buffered(accumulator) => $hasher(accumulator.contiguous()?.as_bytes(), self.seed),
+                    State::Streaming(stream) => stream.digest(),
+                };
+
+                Ok(*hash.cached.insert(digest))
+            }
+        }
+
         #[pymethods]
         impl $name {
             #[getter(__class__)]
@@ -227,78 +281,187 @@ macro_rules! impl_hashlib {
                 1
             }
 
-            fn digest(&self) -> [u8; $digest_size] {
-                $hasher(self.buffer.as_bytes(), self.seed).to_le_bytes()
+            fn digest(&self, py: Python<'_>) -> PyResult<[u8; $digest_size]> {
+                Ok(self.hash(py)?.to_le_bytes())
             }
 
-            fn hexdigest(&self) -> String {
-                $hasher(self.buffer.as_bytes(), self.seed).hexdigest()
+            fn hexdigest(&self, py: Python<'_>) -> PyResult<String> {
+                Ok(self.hash(py)?.hexdigest())
             }
 
-            fn update(&mut self, py: Python, data: PyBuffer<u8>) -> PyResult<()> {
-                let slice = self.buffer.as_bytes();
-                let new_slice = data.as_bytes();
+            fn update(&self, py: Python<'_>, data: PyBytesLike<'_>) -> PyResult<()> {
+                let mut hash = self.lock(py);
 
-                let mut combined = Vec::with_capacity(slice.len() + new_slice.len());
-                combined.extend_from_slice(slice);
-                combined.extend_from_slice(new_slice);
-                self.buffer = PyBuffer::get(&combined.into_bound_py_any(py)?)?;
+                match &mut hash.state {
+                    State::Buffered(accumulator) => accumulator.append(data.as_bytes())?,
+                    State::Streaming(stream) => stream.update(data.as_bytes()),
+                }
 
+                hash.cached = None;
                 Ok(())
             }
 
-            fn copy(&self, py: Python) -> PyResult<Self> {
-                let new_hashlib = Self {
-                    seed: self.seed,
-                    buffer: PyBuffer::get(&self.buffer.as_bytes().into_bound_py_any(py)?)?,
+            fn copy(&self, py: Python<'_>) -> PyResult<Self> {
+                let mut hash = self.lock(py);
+                let state = match &mut hash.state {
+                    State::Buffered(accumulator) => State::Buffered(accumulator.copy()?),
+                    State::Streaming(stream) => State::Streaming(stream.clone()),
                 };
 
-                Ok(new_hashlib)
+                Ok(Self::new(self.seed, state, hash.cached))
             }
         }
 
         #[pyfunction]
-        #[pyo3(signature = (data = None, *, seed = 0, **_kwargs))]
+        #[pyo3(signature = (data = None, *, seed = 0, streaming = false, memory_budget = None, lazy = false, **_kwargs))]
         fn $function_name(
             py: Python<'_>,
-            data: Option<PyBuffer<u8>>,
+            data: Option<ContiguousBuffer>,
             seed: i64,
+            streaming: bool,
+            memory_budget: Option<usize>,
+            lazy: bool,
             _kwargs: Option<Bound<'_, PyDict>>,
         ) -> PyResult<$name> {
-            let buffer = data.map_or_else(|| PyBuffer::get(&b"".into_bound_py_any(py)?), Ok)?;
-            Ok($name { seed, buffer })
+            let state = match streaming {
+                true => {
+                    let mut stream = Stream::new(seed);
+
+                    if let Some(buffer) = data {
+                        stream.update(buffer.as_bytes());
+                    }
+
+                    State::Streaming(stream)
+                }
+                false => {
+                    let mut accumulator = Accumulator::new(memory_budget);
+
+                    if let Some(buffer) = data {
+                        // `bytes` cannot be mutated, so only the other buffers are copied unless they are borrowed lazily
+                        if lazy || buffer.is_bytes(py) {
+                            accumulator.borrow(buffer)?;
+                        } else {
+                            accumulator.append(buffer.as_bytes())?;
+                        }
+                    }
+
+                    State::Buffered(accumulator)
+                }
+            };
+
+            Ok($name::new(seed, state, None))
         }
     };
 }
 
 #[pyfunction]
-#[pyo3(signature = (name, data = None, *, seed = 0, **kwargs))]
+#[pyo3(signature = (name, data = None, *, seed = 0, streaming = false, memory_budget = None, lazy = false, **kwargs))]
+#[allow(clippy::too_many_arguments)]
 fn new<'py>(
     py: Python<'py>,
     name: &str,
-    data: Option<PyBuffer<u8>>,
+    data: Option<ContiguousBuffer>,
     seed: i64,
+    streaming: bool,
+    memory_budget: Option<usize>,
+    lazy: bool,
     kwargs: Option<Bound<'py, PyDict>>,
 ) -> PyResult<Bound<'py, PyAny>> {
     match name {
-        "gxhash32" => gxhash32(py, data, seed, kwargs)?.into_bound_py_any(py),
-        "gxhash64" => gxhash64(py, data, seed, kwargs)?.into_bound_py_any(py),
-        "gxhash128" => gxhash128(py, data, seed, kwargs)?.into_bound_py_any(py),
+        "gxhash32" => gxhash32(py, data, seed, streaming, memory_budget, lazy, kwargs)?.into_bound_py_any(py),
+        "gxhash64" => gxhash64(py, data, seed, streaming, memory_budget, lazy, kwargs)?.into_bound_py_any(py),
+        "gxhash128" => gxhash128(py, data, seed, streaming, memory_budget, lazy, kwargs)?.into_bound_py_any(py),
         _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
             "unsupported hash type: {name}",
         ))),
     }
 }
 
+/// The names of the algorithms that `install` registers with the standard hashlib module
+const ALGORITHMS: [&str; 3] = ["gxhash32", "gxhash64", "gxhash128"];
+
+/// The `hashlib.new` installed by `install`, which creates `GxHash` objects and delegates any other algorithm to the
+/// function that it replaced
+#[cfg_attr(Py_3_10, pyclass(frozen, immutable_type, module = "gxhash.gxhashlib"))]
+#[cfg_attr(not(Py_3_10), pyclass(frozen, module = "gxhash.gxhashlib"))]
+struct HashlibNew {
+    new: Py<PyAny>,
+    original: Py<PyAny>,
+}
+
+#[pymethods]
+impl HashlibNew {
+    #[pyo3(signature = (name, *args, **kwargs))]
+    fn __call__<'py>(
+        &self,
+        name: &Bound<'py, PyAny>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = name.py();
+        let arguments = PyTuple::new(py, std::iter::once(name.clone()).chain(args).collect::<Vec<_>>())?;
+        let algorithm = name.cast::<PyString>().ok().and_then(|name| name.to_str().ok());
+
+        match algorithm {
+            Some(algorithm) if ALGORITHMS.contains(&algorithm) => self.new.bind(py).call(arguments, kwargs),
+            _ => self.original.bind(py).call(arguments, kwargs),
+        }
+    }
+}
+
+#[pyfunction]
+fn install(py: Python<'_>) -> PyResult<()> {
+    let hashlib = py.import(intern!(py, "hashlib"))?;
+    let original = hashlib.getattr(intern!(py, "new"))?;
+
+    if !original.is_instance_of::<HashlibNew>() {
+        hashlib.setattr(
+            intern!(py, "new"),
+            HashlibNew {
+                new: pyo3::wrap_pyfunction!(new, py)?.into_any().unbind(),
+                original: original.unbind(),
+            },
+        )?;
+    }
+
+    // `algorithms_guaranteed` lists the algorithms of every Python build, which does not include third-party ones
+    hashlib
+        .getattr(intern!(py, "algorithms_available"))?
+        .call_method1(intern!(py, "update"), (ALGORITHMS,))?;
+
+    Ok(())
+}
+
+#[pyfunction]
+fn uninstall(py: Python<'_>) -> PyResult<()> {
+    let hashlib = py.import(intern!(py, "hashlib"))?;
+
+    // a `hashlib.new` installed after ours is kept, as restoring the original would also remove it
+    if let Ok(installed) = hashlib.getattr(intern!(py, "new"))?.cast_into::<HashlibNew>() {
+        hashlib.setattr(intern!(py, "new"), installed.get().original.clone_ref(py))?;
+    }
+
+    let algorithms = hashlib.getattr(intern!(py, "algorithms_available"))?;
+
+    for algorithm in ALGORITHMS {
+        algorithms.call_method1(intern!(py, "discard"), (algorithm,))?;
+    }
+
+    Ok(())
+}
+
 #[pyfunction]
-#[pyo3(signature = (fileobj, digest, /, *, seed = 0, **kwargs))]
+#[pyo3(signature = (fileobj, digest, /, *, seed = 0, progress = None, progress_interval = None, **kwargs))]
 fn file_digest<'py>(
     py: Python<'py>,
     fileobj: &Bound<'py, PyAny>,
     digest: &Bound<'py, PyAny>,
     seed: i64,
+    progress: Option<Py<PyAny>>,
+    progress_interval: Option<NonZeroUsize>,
     kwargs: Option<Bound<'py, PyDict>>,
 ) -> PyResult<Bound<'py, PyAny>> {
+    let mut progress = Progress::new(progress, progress_interval);
     let data = fileobj.call_method0(intern!(py, "getbuffer")).or_else(|_| {
         let mut file = ManuallyDrop::new(unsafe {
             let fileno = fileobj.call_method0(intern!(py, "fileno"))?.extract::<i32>()?;
@@ -316,23 +479,36 @@ fn file_digest<'py>(
         let bytes = pyo3::types::PyBytes::new_with(
             py,
             usize::try_from(file.metadata()?.len() - file.stream_position()?)?,
-            |buffer| py.detach(|| (&*file).read_exact(buffer).map_err(Into::into)),
+            |buffer| py.detach(|| file::read_exact(&*file, buffer, progress.as_mut())),
         );
 
         bytes.map(Bound::into_any)
     })?;
 
-    match digest.cast::<pyo3::types::PyString>() {
-        Ok(name) => new(py, name.extract()?, Some(PyBuffer::get(&data)?), seed, kwargs),
+    // the buffer of a `BytesIO` is borrowed rather than copied, so it cannot be resized while the hash object holds it
+    let hasher = match digest.cast::<pyo3::types::PyString>() {
+        Ok(name) => new(
+            py,
+            name.extract()?,
+            Some(data.extract()?),
+            seed,
+            false,
+            None,
+            true,
+            kwargs,
+        ),
         _ => digest
             .call0()
             .and_then(|hasher| hasher.call_method1(intern!(py, "update"), (&data,)).map(|_| hasher)),
-    }
+    }?;
+
+    progress::finish(progress.as_mut(), data.len()?)?;
+    Ok(hasher)
 }
 
-impl_hashlib!(GxHashLib32, gxhash32, 4, gxhash_core::gxhash32);
-impl_hashlib!(GxHashLib64, gxhash64, 8, gxhash_core::gxhash64);
-impl_hashlib!(GxHashLib128, gxhash128, 16, gxhash_core::gxhash128);
+impl_hashlib!(GxHashLib32, gxhash32, 4, u32, gxhash_core::gxhash32);
+impl_hashlib!(GxHashLib64, gxhash64, 8, u64, gxhash_core::gxhash64);
+impl_hashlib!(GxHashLib128, gxhash128, 16, u128, gxhash_core::gxhash128);
 
 /// hashlib-compatible `GxHash` API
 ///
@@ -344,11 +520,23 @@ impl_hashlib!(GxHashLib128, gxhash128, 16, gxhash_core::gxhash128);
 ///
 /// The functions provide a compatible interface with Python's built-in hashlib module.
 ///
-/// * gxhash32(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False) -> HASH
-/// * gxhash64(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False) -> HASH
-/// * gxhash128(data: Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False) -> HASH
-/// * new(name: str, data: str | Buffer = b"", *, seed: int = 0, usedforsecurity: bool = False) -> HASH
-/// * `file_digest(fileobj`: `BytesIOLike` | `FileLike`, digest: str | Callable[[], HASH], /, *, seed: int = 0) -> HASH
+/// * gxhash32(data: Buffer = b"", *, seed: int = 0, streaming: bool = False, `memory_budget`: int | None = None, lazy: bool = False, usedforsecurity: bool = False) -> HASH
+/// * gxhash64(data: Buffer = b"", *, seed: int = 0, streaming: bool = False, `memory_budget`: int | None = None, lazy: bool = False, usedforsecurity: bool = False) -> HASH
+/// * gxhash128(data: Buffer = b"", *, seed: int = 0, streaming: bool = False, `memory_budget`: int | None = None, lazy: bool = False, usedforsecurity: bool = False) -> HASH
+/// * new(name: str, data: str | Buffer = b"", *, seed: int = 0, streaming: bool = False, `memory_budget`: int | None = None, lazy: bool = False, usedforsecurity: bool = False) -> HASH
+/// * `file_digest(fileobj`: `BytesIOLike` | `FileLike`, digest: str | Callable[[], HASH], /, *, seed: int = 0, progress: Callable[[int], object] | None = None, `progress_interval`: int = 16 << 20) -> HASH
+/// * `install()` -> None
+/// * `uninstall()` -> None
+///
+/// HASH objects created with `streaming=True` hash their input incrementally in constant memory,
+/// but their digests are not equal to the digests of the non-streaming HASH objects.
+/// Non-streaming HASH objects accumulate their input in linear time and cache their digest until the next update.
+/// Past their `memory_budget` in bytes, they spill their input to an anonymous temporary file mapped when hashed.
+/// Copies share the input accumulated so far with the original, so forking a HASH object costs constant memory.
+/// Like hashlib, HASH objects consume their input immediately, unless `lazy=True` borrows the initial buffer until it
+/// is hashed, which must then not be mutated.
+/// `install` registers the `GxHash` algorithms with `hashlib.new` and the algorithm sets of the standard hashlib module,
+/// so that code which takes an algorithm name can use them unchanged, and `uninstall` removes them again.
 ///
 /// The HASH objects returned by these functions again provide the standard HASH methods and properties.
 ///
@@ -374,12 +562,16 @@ pub mod hashlib_module {
     #[pymodule_export]
     use super::gxhash64;
     #[pymodule_export]
+    use super::install;
+    #[pymodule_export]
     use super::new;
+    #[pymodule_export]
+    use super::uninstall;
 
     #[pymodule_init]
     fn init(m: &pyo3::Bound<'_, types::PyModule>) -> pyo3::PyResult<()> {
         let py = m.py();
-        let algorithms_available = types::PySet::new(py, ["gxhash32", "gxhash64", "gxhash128"])?;
+        let algorithms_available = types::PySet::new(py, super::ALGORITHMS)?;
 
         m.add("algorithms_available", &algorithms_available)?;
         m.add("algorithms_guaranteed", &algorithms_available)
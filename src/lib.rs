@@ -1,10 +1,28 @@
+mod accumulator;
 mod buffer;
+mod coalesce;
 mod core;
+mod coroutine;
+mod executor;
+mod file;
+mod fork;
+mod futures;
 mod hashlib;
+mod limiter;
+mod offload;
+mod progress;
+mod runtime;
+mod stream;
+mod timeout;
+mod tree;
 
 pub use core::GxHash128;
 pub use core::GxHash32;
 pub use core::GxHash64;
+pub use executor::GxHashExecutor;
+pub use executor::GxHashTask;
+pub use executor::EXECUTOR_ABI_VERSION;
+pub use executor::EXECUTOR_CAPSULE_NAME;
 
 #[pyo3::pymodule(name = "gxhash", gil_used = false)]
 pub mod gxhash_py {
@@ -0,0 +1,102 @@
+use crate::executor;
+use crate::runtime;
+
+use pyo3::Python;
+
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+const BYTES_PER_PERMIT: usize = 1 << 10;
+
+fn byte_permits(bytes: usize) -> u32 {
+    u32::try_from(bytes.div_ceil(BYTES_PER_PERMIT)).unwrap_or(u32::MAX)
+}
+
+/// A slot for an offloaded hash, which is freed once the hash completes or is skipped
+pub(crate) struct Permit {
+    _count: Option<OwnedSemaphorePermit>,
+    _bytes: Option<OwnedSemaphorePermit>,
+}
+
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(waiting: &'a AtomicUsize) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Bounds the number and the total size of the hashes that are offloaded at once
+///
+/// Sizes are accounted in kibibytes, and an input larger than the byte limit waits for the whole limit instead.
+pub(crate) struct Limiter {
+    count: Option<Arc<Semaphore>>,
+    bytes: Option<(Arc<Semaphore>, u32)>,
+    waiting: AtomicUsize,
+}
+
+impl Limiter {
+    pub(crate) fn new(max_in_flight: Option<NonZeroUsize>, max_in_flight_bytes: Option<NonZeroUsize>) -> Option<Self> {
+        if max_in_flight.is_none() && max_in_flight_bytes.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            count: max_in_flight.map(|count| Arc::new(Semaphore::new(count.get().min(Semaphore::MAX_PERMITS)))),
+            bytes: max_in_flight_bytes.map(|bytes| {
+                let permits = byte_permits(bytes.get());
+                (Arc::new(Semaphore::new(permits as usize)), permits)
+            }),
+            waiting: AtomicUsize::new(0),
+        })
+    }
+
+    async fn acquire(&self, size: usize) -> Permit {
+        let _waiting = Waiting::new(&self.waiting);
+        let count = match &self.count {
+            Some(semaphore) => Arc::clone(semaphore).acquire_owned().await.ok(),
+            None => None,
+        };
+        let bytes = match &self.bytes {
+            Some((semaphore, limit)) => Arc::clone(semaphore)
+                .acquire_many_owned(byte_permits(size).min(*limit))
+                .await
+                .ok(),
+            None => None,
+        };
+
+        Permit {
+            _count: count,
+            _bytes: bytes,
+        }
+    }
+}
+
+/// Waits for a slot to offload `size` bytes, unless offloaded hashes are not limited
+pub(crate) async fn acquire(size: usize) -> Option<Permit> {
+    let limiter = runtime::limiter()?;
+    Some(limiter.acquire(size).await)
+}
+
+/// Blocks the calling thread until there is a slot to offload `size` bytes, unless offloaded hashes are not limited
+pub(crate) fn acquire_blocking(py: Python<'_>, size: usize) -> Option<Permit> {
+    let limiter = runtime::limiter()?;
+    Some(py.detach(|| executor::block_on(limiter.acquire(size))))
+}
+
+/// Returns the number of hashes waiting for a slot before they can be offloaded
+pub(crate) fn queue_depth() -> usize {
+    runtime::limiter().map_or(0, |limiter| limiter.waiting.load(Ordering::Relaxed))
+}
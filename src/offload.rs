@@ -0,0 +1,166 @@
+use crate::executor;
+use crate::executor::Executor;
+use crate::fork::ForkSafe;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::pybacked::PyBackedStr;
+use pyo3::Borrowed;
+use pyo3::FromPyObject;
+use pyo3::PyAny;
+use pyo3::PyErr;
+use pyo3::PyResult;
+
+use std::hint::black_box;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Once;
+use std::time::Duration;
+use std::time::Instant;
+
+const CALIBRATION_ROUNDS: usize = 15;
+const CALIBRATION_SIZE: usize = 1 << 20;
+const MIN_OFFLOAD_THRESHOLD: usize = 4 << 10;
+const MAX_OFFLOAD_THRESHOLD: usize = 64 << 20;
+const DEFAULT_OFFLOAD_THRESHOLD: usize = 4 << 20;
+
+/// The input size from which synchronous hashes release the GIL, which is fixed so that they never calibrate
+pub(crate) const DETACH_THRESHOLD: usize = 4 << 20;
+
+static MANAGED_CALIBRATION: ForkSafe<Calibration> = ForkSafe::new(Calibration::new);
+
+#[derive(Clone, Copy)]
+pub(crate) enum OffloadThreshold {
+    Calibrated,
+    Bytes(usize),
+    Always,
+    Never,
+}
+
+impl OffloadThreshold {
+    pub(crate) fn offloads(self, size: usize, executor: &Executor) -> bool {
+        match self {
+            Self::Calibrated => size >= executor.calibration().calibrated_in_background(executor),
+            Self::Bytes(threshold) => size >= threshold,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+impl FromPyObject<'_, '_> for OffloadThreshold {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        if let Ok(threshold) = obj.extract::<usize>() {
+            return Ok(Self::Bytes(threshold));
+        }
+
+        match obj.extract::<PyBackedStr>().as_deref() {
+            Ok("always") => Ok(Self::Always),
+            Ok("never") => Ok(Self::Never),
+            _ => Err(PyValueError::new_err(
+                "offload_threshold must be a non-negative int, 'always' or 'never'",
+            )),
+        }
+    }
+}
+
+fn median(samples: PyResult<Vec<Duration>>) -> PyResult<Duration> {
+    let mut samples = samples?;
+    samples.sort_unstable();
+    Ok(samples[samples.len() / 2])
+}
+
+fn round_trip(executor: &Executor) -> PyResult<Duration> {
+    let start = Instant::now();
+    executor::block_on(executor.spawn_blocking(|_| ())?.join())?;
+    Ok(start.elapsed())
+}
+
+/// The offload threshold measured against an executor, which is measured once on first use
+pub(crate) struct Calibration {
+    threshold: AtomicUsize,
+    once: Once,
+    started: AtomicBool,
+}
+
+impl Calibration {
+    pub(crate) const fn new() -> Self {
+        Self {
+            threshold: AtomicUsize::new(DEFAULT_OFFLOAD_THRESHOLD),
+            once: Once::new(),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the calibration of the managed runtime, which a forked child measures again
+    pub(crate) fn managed() -> &'static Self {
+        MANAGED_CALIBRATION.get()
+    }
+
+    /// Measures the input size at which hashing inline costs as much as a round trip through `executor`
+    ///
+    /// The result is clamped, so that a noisy measurement cannot make every hash offload or none at all.
+    pub(crate) fn calibrate(&self, executor: &Executor) -> PyResult<usize> {
+        let bytes = vec![0u8; CALIBRATION_SIZE];
+        round_trip(executor)?;
+
+        let round_trip = median((0..CALIBRATION_ROUNDS).map(|_| round_trip(executor)).collect())?;
+        let inline = median(
+            (0..CALIBRATION_ROUNDS)
+                .map(|_| {
+                    let start = Instant::now();
+                    black_box(gxhash_core::gxhash64(black_box(&bytes), 0));
+                    Ok(start.elapsed())
+                })
+                .collect(),
+        )?;
+
+        let crossover = round_trip.as_nanos() * CALIBRATION_SIZE as u128 / inline.as_nanos().max(1);
+        let threshold = usize::try_from(crossover)
+            .unwrap_or(MAX_OFFLOAD_THRESHOLD)
+            .clamp(MIN_OFFLOAD_THRESHOLD, MAX_OFFLOAD_THRESHOLD);
+
+        self.threshold.store(threshold, Ordering::Relaxed);
+        Ok(threshold)
+    }
+
+    /// Returns the calibrated offload threshold, calibrating on the calling thread on first use
+    ///
+    /// Concurrent callers wait for the first calibration instead of repeating it. The default threshold is kept if
+    /// `executor` rejects the round trips that measure it.
+    pub(crate) fn calibrated(&self, executor: &Executor) -> usize {
+        self.once.call_once(|| {
+            let _ = self.calibrate(executor);
+        });
+
+        self.threshold.load(Ordering::Relaxed)
+    }
+
+    /// Returns the calibrated offload threshold without waiting for it, so that an event loop is never blocked by the
+    /// calibration
+    ///
+    /// The first call starts the calibration on a background thread, and the default threshold is returned until it ends.
+    fn calibrated_in_background(&self, executor: &Executor) -> usize {
+        if !self.once.is_completed() && !self.started.swap(true, Ordering::Relaxed) {
+            let executor = executor.clone();
+            let spawned = std::thread::Builder::new()
+                .name("gxhash-calibration".into())
+                .spawn(move || executor.calibration().calibrated(&executor));
+
+            if spawned.is_err() {
+                self.started.store(false, Ordering::Relaxed);
+            }
+        }
+
+        self.threshold.load(Ordering::Relaxed)
+    }
+}
+
+/// Forgets the calibration of the managed runtime inherited from the parent process, which may still be in progress on
+/// a thread that does not exist in a forked child
+#[cfg(unix)]
+pub(crate) fn reset_after_fork() {
+    MANAGED_CALIBRATION.reset();
+}
@@ -0,0 +1,59 @@
+use pyo3::Py;
+use pyo3::PyAny;
+use pyo3::PyResult;
+use pyo3::Python;
+
+use std::num::NonZeroUsize;
+
+/// The number of bytes processed between two reports, unless another interval is given
+const DEFAULT_INTERVAL: usize = 16 << 20;
+
+/// Reports the number of bytes processed so far to a Python callable, on the thread doing the work
+///
+/// A report is made whenever at least `interval` bytes were processed since the previous one, and once more when the
+/// work completes. Raising from the callable aborts the hash with that exception.
+pub(crate) struct Progress {
+    callback: Py<PyAny>,
+    interval: usize,
+    reported: Option<usize>,
+}
+
+impl Progress {
+    pub(crate) fn new(callback: Option<Py<PyAny>>, interval: Option<NonZeroUsize>) -> Option<Self> {
+        Some(Self {
+            callback: callback?,
+            interval: interval.map_or(DEFAULT_INTERVAL, NonZeroUsize::get),
+            reported: None,
+        })
+    }
+
+    pub(crate) fn interval(&self) -> usize {
+        self.interval
+    }
+
+    pub(crate) fn update(&mut self, processed: usize) -> PyResult<()> {
+        if processed - self.reported.unwrap_or(0) < self.interval {
+            return Ok(());
+        }
+
+        self.report(processed)
+    }
+
+    pub(crate) fn finish(&mut self, processed: usize) -> PyResult<()> {
+        if self.reported == Some(processed) {
+            return Ok(());
+        }
+
+        self.report(processed)
+    }
+
+    fn report(&mut self, processed: usize) -> PyResult<()> {
+        self.reported = Some(processed);
+        Python::attach(|py| self.callback.call1(py, (processed,)).map(drop))
+    }
+}
+
+/// Reports that the work completed after processing `processed` bytes, unless progress is not reported
+pub(crate) fn finish(progress: Option<&mut Progress>, processed: usize) -> PyResult<()> {
+    progress.map_or(Ok(()), |progress| progress.finish(processed))
+}
@@ -0,0 +1,129 @@
+use crate::fork::ForkSafe;
+use crate::limiter::Limiter;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use tokio::runtime::Builder;
+use tokio::runtime::Handle;
+use tokio::runtime::Runtime;
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+const WORKER_THREADS_ENV: &str = "GXHASH_WORKER_THREADS";
+
+struct State {
+    runtime: Option<Runtime>,
+    limiter: Option<Arc<Limiter>>,
+}
+
+static STATE: ForkSafe<Mutex<State>> = ForkSafe::new(new_state);
+
+// the configuration is kept outside of the state, so that forked children can read it without locking the state
+static WORKER_THREADS: AtomicUsize = AtomicUsize::new(0);
+static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static MAX_IN_FLIGHT_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn load(setting: &AtomicUsize) -> Option<NonZeroUsize> {
+    NonZeroUsize::new(setting.load(Ordering::Relaxed))
+}
+
+fn store(setting: &AtomicUsize, value: Option<NonZeroUsize>) {
+    setting.store(value.map_or(0, NonZeroUsize::get), Ordering::Relaxed);
+}
+
+fn new_state() -> Mutex<State> {
+    Mutex::new(State {
+        runtime: None,
+        limiter: Limiter::new(load(&MAX_IN_FLIGHT), load(&MAX_IN_FLIGHT_BYTES)).map(Arc::new),
+    })
+}
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.get().lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn worker_threads_from_env() -> PyResult<Option<NonZeroUsize>> {
+    let Ok(worker_threads) = std::env::var(WORKER_THREADS_ENV) else {
+        return Ok(None);
+    };
+
+    worker_threads.parse().map(Some).map_err(|_| {
+        PyValueError::new_err(format!(
+            "{WORKER_THREADS_ENV} must be a positive integer, got {worker_threads:?}"
+        ))
+    })
+}
+
+/// Returns a handle to the runtime, starting it on first use
+///
+/// The worker count is taken from `configure`, then from `GXHASH_WORKER_THREADS`, and otherwise left to Tokio.
+/// It bounds both the async workers and the blocking pool, as the blocking pool is where the hashing happens.
+pub(crate) fn handle() -> PyResult<Handle> {
+    let mut state = state();
+
+    if let Some(runtime) = &state.runtime {
+        return Ok(runtime.handle().clone());
+    }
+
+    let mut builder = Builder::new_multi_thread();
+
+    if let Some(worker_threads) = load(&WORKER_THREADS).map_or_else(worker_threads_from_env, |n| Ok(Some(n)))? {
+        builder
+            .worker_threads(worker_threads.get())
+            .max_blocking_threads(worker_threads.get());
+    }
+
+    let runtime = builder.build()?;
+    let handle = runtime.handle().clone();
+    state.runtime = Some(runtime);
+
+    Ok(handle)
+}
+
+pub(crate) fn configure(
+    worker_threads: Option<NonZeroUsize>,
+    max_in_flight: Option<NonZeroUsize>,
+    max_in_flight_bytes: Option<NonZeroUsize>,
+) -> PyResult<()> {
+    let mut state = state();
+
+    if state.runtime.is_some() {
+        return Err(PyRuntimeError::new_err(
+            "the runtime is already running, call shutdown_runtime() before configuring it",
+        ));
+    }
+
+    store(&WORKER_THREADS, worker_threads);
+    store(&MAX_IN_FLIGHT, max_in_flight);
+    store(&MAX_IN_FLIGHT_BYTES, max_in_flight_bytes);
+    state.limiter = Limiter::new(max_in_flight, max_in_flight_bytes).map(Arc::new);
+    Ok(())
+}
+
+/// Returns the limiter of offloaded hashes, if any limit was configured
+pub(crate) fn limiter() -> Option<Arc<Limiter>> {
+    state().limiter.clone()
+}
+
+/// Stops the runtime without waiting for in-flight hashes, which still complete on their own threads
+pub(crate) fn shutdown() {
+    if let Some(runtime) = state().runtime.take() {
+        runtime.shutdown_background();
+    }
+}
+
+/// Forgets the runtime inherited from the parent process, as its threads do not exist in a forked child
+///
+/// The state is replaced rather than locked, since a thread of the parent may have been holding it while forking.
+/// The limiter is replaced as well, since the slots held by the hashes of the parent would never be freed.
+#[cfg(unix)]
+pub(crate) fn reset_after_fork() {
+    STATE.reset();
+}
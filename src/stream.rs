@@ -0,0 +1,76 @@
+use gxhash_core::GxHasher;
+use std::hash::Hasher;
+
+const BLOCK_SIZE: usize = 64 << 10;
+
+pub(crate) trait FromHasher {
+    fn from_hasher(hasher: &GxHasher) -> Self;
+}
+
+impl FromHasher for u32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_hasher(hasher: &GxHasher) -> Self {
+        hasher.finish() as u32
+    }
+}
+
+impl FromHasher for u64 {
+    fn from_hasher(hasher: &GxHasher) -> Self {
+        hasher.finish()
+    }
+}
+
+impl FromHasher for u128 {
+    fn from_hasher(hasher: &GxHasher) -> Self {
+        hasher.finish_u128()
+    }
+}
+
+/// An incremental `GxHasher` that is fed in fixed-size blocks
+///
+/// Input is regrouped into `BLOCK_SIZE` blocks before it reaches the hasher,
+/// so the digest only depends on the concatenated input and not on how it was split across updates.
+/// The digest is not the same as the one-shot digest of the concatenated input.
+#[derive(Clone)]
+pub(crate) struct Stream {
+    hasher: GxHasher,
+    block: Vec<u8>,
+}
+
+impl Stream {
+    pub(crate) fn new(seed: i64) -> Self {
+        Self {
+            hasher: GxHasher::with_seed(seed),
+            block: Vec::new(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        if !self.block.is_empty() {
+            let (head, tail) = data.split_at(data.len().min(BLOCK_SIZE - self.block.len()));
+            self.block.extend_from_slice(head);
+            data = tail;
+
+            if self.block.len() < BLOCK_SIZE {
+                return;
+            }
+
+            self.hasher.write(&self.block);
+            self.block.clear();
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        blocks.by_ref().for_each(|block| self.hasher.write(block));
+        self.block.extend_from_slice(blocks.remainder());
+    }
+
+    pub(crate) fn digest<T: FromHasher>(&self) -> T {
+        let mut hasher = self.hasher.clone();
+
+        if !self.block.is_empty() {
+            hasher.write(&self.block);
+        }
+
+        T::from_hasher(&hasher)
+    }
+}
@@ -0,0 +1,199 @@
+use crate::core::GxHashAsyncError;
+use crate::core::GxHashTimeoutError;
+use crate::fork::ForkSafe;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::Borrowed;
+use pyo3::FromPyObject;
+use pyo3::PyAny;
+use pyo3::PyErr;
+use pyo3::PyResult;
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Timeout(Duration);
+
+impl FromPyObject<'_, '_> for Timeout {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        obj.extract::<f64>()
+            .ok()
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+            .map(Self)
+            .ok_or_else(|| PyValueError::new_err("timeout must be a non-negative number of seconds"))
+    }
+}
+
+/// The pending deadlines, which a single thread wakes once they pass
+///
+/// Deadlines are kept here rather than by the managed runtime, so that a hasher offloading to a foreign executor never
+/// starts the runtime.
+struct Timers {
+    deadlines: Mutex<BTreeMap<(Instant, u64), Waker>>,
+    changed: Condvar,
+    next: AtomicU64,
+    thread: OnceLock<bool>,
+}
+
+static TIMERS: ForkSafe<Timers> = ForkSafe::new(Timers::new);
+
+impl Timers {
+    fn new() -> Self {
+        Self {
+            deadlines: Mutex::new(BTreeMap::new()),
+            changed: Condvar::new(),
+            next: AtomicU64::new(0),
+            thread: OnceLock::new(),
+        }
+    }
+
+    fn deadlines(&self) -> MutexGuard<'_, BTreeMap<(Instant, u64), Waker>> {
+        self.deadlines.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Starts the thread that wakes the deadlines on first use
+    fn start(&'static self) -> PyResult<()> {
+        let started = self.thread.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("gxhash-timer".into())
+                .spawn(|| self.run())
+                .is_ok()
+        });
+
+        if *started {
+            Ok(())
+        } else {
+            Err(GxHashAsyncError::new_err("the timer thread could not be started"))
+        }
+    }
+
+    /// Wakes the deadlines as they pass, without holding the lock while waking, as wakers may wait for the GIL
+    fn run(&self) {
+        let mut deadlines = self.deadlines();
+
+        loop {
+            let now = Instant::now();
+            let pending = deadlines.split_off(&(now, u64::MAX));
+            let expired = std::mem::replace(&mut *deadlines, pending);
+
+            if !expired.is_empty() {
+                drop(deadlines);
+                expired.into_values().for_each(Waker::wake);
+                deadlines = self.deadlines();
+                continue;
+            }
+
+            deadlines = match deadlines.keys().next().map(|&(deadline, _)| deadline - now) {
+                Some(timeout) => {
+                    self.changed
+                        .wait_timeout(deadlines, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self.changed.wait(deadlines).unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+    }
+}
+
+/// A future that is ready once its deadline has passed
+struct Deadline {
+    at: Instant,
+    key: Option<(Instant, u64)>,
+}
+
+impl Deadline {
+    fn after(duration: Duration) -> PyResult<Self> {
+        TIMERS.get().start()?;
+
+        Ok(Self {
+            at: Instant::now() + duration,
+            key: None,
+        })
+    }
+}
+
+impl Future for Deadline {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.at {
+            return Poll::Ready(());
+        }
+
+        // a forked child replaces the timers, so their thread is started again if the deadline outlived the fork
+        let timers = TIMERS.get();
+        let _ = timers.start();
+        let at = self.at;
+        let key = *self
+            .key
+            .get_or_insert_with(|| (at, timers.next.fetch_add(1, Ordering::Relaxed)));
+        let mut deadlines = timers.deadlines();
+        deadlines.insert(key, cx.waker().clone());
+
+        if deadlines.keys().next() == Some(&key) {
+            timers.changed.notify_one();
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            TIMERS.get().deadlines().remove(&key);
+        }
+    }
+}
+
+/// Awaits `future`, raising `GxHashTimeoutError` if it is not ready within `timeout`
+///
+/// The deadline is kept by a timer thread, so dropping `future` on expiry also cancels the tasks it awaits.
+pub(crate) async fn within<F, R>(timeout: Option<Timeout>, future: F) -> PyResult<R>
+where
+    F: Future<Output = PyResult<R>>,
+{
+    let Some(Timeout(duration)) = timeout else {
+        return future.await;
+    };
+
+    let mut deadline = Deadline::after(duration)?;
+    let mut future = std::pin::pin!(future);
+
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(output);
+        }
+
+        Pin::new(&mut deadline).poll(cx).map(|()| {
+            Err(GxHashTimeoutError::new_err(format!(
+                "the hash did not complete within {} seconds",
+                duration.as_secs_f64()
+            )))
+        })
+    })
+    .await
+}
+
+/// Forgets the timer thread inherited from the parent process, as it does not exist in a forked child
+#[cfg(unix)]
+pub(crate) fn reset_after_fork() {
+    TIMERS.reset();
+}
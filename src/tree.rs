@@ -0,0 +1,104 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::Borrowed;
+use pyo3::FromPyObject;
+use pyo3::PyAny;
+use pyo3::PyErr;
+
+use std::ops::Range;
+
+/// The default size of the chunks that `hash_parallel` splits its input into, which is part of its output
+pub(crate) const DEFAULT_CHUNK_SIZE: usize = 4 << 20;
+
+/// The smallest chunk size, so that the leaves of an input take at most 1/256 of its size in memory
+const MIN_CHUNK_SIZE: usize = 4 << 10;
+
+const HASH_SIZE: usize = size_of::<u128>();
+
+/// Prefixes the hash of a chunk, so that a leaf never hashes the same bytes as a node
+const LEAF_TAG: u8 = 0;
+
+/// Prefixes the children of a node, so that a node never hashes the same bytes as a leaf
+const NODE_TAG: u8 = 1;
+
+#[derive(Clone, Copy)]
+pub(crate) struct ChunkSize(usize);
+
+impl ChunkSize {
+    pub(crate) fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl FromPyObject<'_, '_> for ChunkSize {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        obj.extract::<usize>()
+            .ok()
+            .filter(|&chunk_size| chunk_size >= MIN_CHUNK_SIZE)
+            .map(Self)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("chunk_size must be an int of at least {MIN_CHUNK_SIZE} bytes"))
+            })
+    }
+}
+
+/// Hashes a chunk of the input into a leaf of the tree
+///
+/// The chunk is hashed first and its hash is then tagged, so that the chunk does not have to be copied.
+fn leaf(chunk: &[u8], seed: i64) -> u128 {
+    let mut tagged = [LEAF_TAG; 1 + HASH_SIZE];
+    tagged[1..].copy_from_slice(&gxhash_core::gxhash128(chunk, seed).to_le_bytes());
+    gxhash_core::gxhash128(&tagged, seed)
+}
+
+fn node(left: u128, right: u128, seed: i64) -> u128 {
+    let mut children = [NODE_TAG; 1 + 2 * HASH_SIZE];
+    children[1..=HASH_SIZE].copy_from_slice(&left.to_le_bytes());
+    children[1 + HASH_SIZE..].copy_from_slice(&right.to_le_bytes());
+    gxhash_core::gxhash128(&children, seed)
+}
+
+/// Returns the byte ranges of the chunks of `chunk_size` bytes of an input of `size` bytes, split into `parts` groups
+///
+/// An empty input is made of a single empty chunk, so that every input has at least one leaf.
+pub(crate) fn partition(size: usize, chunk_size: usize, parts: usize) -> Vec<Range<usize>> {
+    let chunks = size.div_ceil(chunk_size).max(1);
+    let chunks_per_part = chunks.div_ceil(parts.max(1));
+
+    (0..chunks)
+        .step_by(chunks_per_part)
+        .map(|first| first * chunk_size..(first + chunks_per_part).saturating_mul(chunk_size).min(size))
+        .collect()
+}
+
+/// Hashes `data` into the leaves of its chunks, in order
+pub(crate) fn leaves(data: &[u8], chunk_size: usize, seed: i64) -> Vec<u128> {
+    if data.is_empty() {
+        return vec![leaf(data, seed)];
+    }
+
+    data.chunks(chunk_size).map(|chunk| leaf(chunk, seed)).collect()
+}
+
+/// Folds `leaves` into the root of the tree, returning the bytes that the output of `hash_parallel` is computed from
+///
+/// Adjacent nodes are hashed together level by level, from left to right, and a trailing node without a sibling is
+/// carried up to the next level as it is. The root is followed by the size of the input, both in little-endian.
+pub(crate) fn root(mut nodes: Vec<u128>, size: usize, seed: i64) -> [u8; HASH_SIZE + size_of::<u64>()] {
+    while nodes.len() > 1 {
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| match *pair {
+                [left, right] => node(left, right, seed),
+                [single] => single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    let mut root = [0u8; HASH_SIZE + size_of::<u64>()];
+    root[..HASH_SIZE].copy_from_slice(&nodes[0].to_le_bytes());
+    root[HASH_SIZE..].copy_from_slice(&(size as u64).to_le_bytes());
+    root
+}
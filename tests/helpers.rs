@@ -1,12 +1,52 @@
+use gxhash::EXECUTOR_ABI_VERSION;
+use gxhash::EXECUTOR_CAPSULE_NAME;
+use gxhash::GxHashExecutor;
+use gxhash::GxHashTask;
 use pyo3::Bound;
 use pyo3::PyAny;
 use pyo3::PyResult;
 use pyo3::Python;
 use pyo3::intern;
+use pyo3::types::IntoPyDict;
 use pyo3::types::PyAnyMethods;
 use pyo3::types::PyBytes;
+use pyo3::types::PyCapsule;
+use pyo3::types::PyDict;
+use pyo3::types::PyDictMethods;
 use pyo3::types::PyMemoryView;
 use pyo3::types::PyModule;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+unsafe extern "C" fn thread_parallelism(_: *mut c_void) -> usize {
+    2
+}
+
+pub unsafe extern "C" fn thread_spawn_blocking(context: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int {
+    let spawned = unsafe { &*context.cast::<AtomicUsize>() };
+    let argument = argument.expose_provenance();
+    spawned.fetch_add(1, Ordering::Relaxed);
+
+    std::thread::spawn(move || unsafe { task(std::ptr::with_exposed_provenance_mut(argument), true) });
+    0
+}
+
+pub fn foreign_executor<'py, T>(
+    py: Python<'py>,
+    context: &'static T,
+    spawn_blocking: unsafe extern "C" fn(*mut c_void, GxHashTask, *mut c_void) -> c_int,
+) -> PyResult<Bound<'py, PyCapsule>> {
+    let executor = GxHashExecutor {
+        version: EXECUTOR_ABI_VERSION,
+        context: std::ptr::from_ref(context).cast_mut().cast(),
+        parallelism: thread_parallelism,
+        spawn_blocking,
+    };
+
+    PyCapsule::new_with_value(py, executor, EXECUTOR_CAPSULE_NAME)
+}
 
 pub(crate) static ONCE: std::sync::Once = std::sync::Once::new();
 
@@ -27,6 +67,8 @@ macro_rules! pytest {
 
 pub trait PythonExt<'py> {
     fn import_asyncio(&self) -> PyResult<Bound<'_, PyModule>>;
+    fn import_trio(&self) -> PyResult<Bound<'_, PyModule>>;
+    fn import_anyio(&self) -> PyResult<Bound<'_, PyModule>>;
     fn import_gxhash(&self) -> PyResult<Bound<'_, PyModule>>;
     fn import_gxhash_hashlib(&self) -> PyResult<Bound<'_, PyModule>>;
     fn import_gxhash32(&self) -> PyResult<Bound<'_, PyAny>>;
@@ -53,6 +95,14 @@ impl<'py> PythonExt<'py> for Python<'py> {
         Ok(asyncio)
     }
 
+    fn import_trio(&self) -> PyResult<Bound<'_, PyModule>> {
+        self.import(intern!(*self, "trio"))
+    }
+
+    fn import_anyio(&self) -> PyResult<Bound<'_, PyModule>> {
+        self.import(intern!(*self, "anyio"))
+    }
+
     fn import_gxhash(&self) -> PyResult<Bound<'_, PyModule>> {
         self.import(intern!(*self, "gxhash.core"))
     }
@@ -119,6 +169,14 @@ where
     obj.call_method1(intern!(py, "hash"), (bytes,))?.extract()
 }
 
+pub fn call_hash_many<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    obj.call_method1(intern!(py, "hash_many"), (items,))?.extract()
+}
+
 pub fn call_hash_async<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, bytes: &[u8]) -> PyResult<T>
 where
     for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
@@ -128,3 +186,360 @@ where
         .call1((obj.call_method1(intern!(py, "hash_async"), (bytes,))?,))?
         .extract()
 }
+
+pub fn call_hash_async_trio<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, bytes: &[u8]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    py.import_trio()?
+        .getattr(intern!(py, "run"))?
+        .call1((obj.getattr(intern!(py, "hash_async"))?, bytes))?
+        .extract()
+}
+
+pub fn call_hash_async_anyio<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    bytes: &[u8],
+    backend: &str,
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    py.import_anyio()?
+        .getattr(intern!(py, "run"))?
+        .call(
+            (obj.getattr(intern!(py, "hash_async"))?, bytes),
+            Some(&[("backend", backend)].into_py_dict(py)?),
+        )?
+        .extract()
+}
+
+pub fn call_hash_submit<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, bytes: &[u8]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    obj.call_method1(intern!(py, "hash_submit"), (bytes,))?
+        .call_method0(intern!(py, "result"))?
+        .extract()
+}
+
+pub fn call_hash_parallel<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    bytes: &[u8],
+    chunk_size: usize,
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "chunk_size"), chunk_size)?;
+
+    obj.call_method(intern!(py, "hash_parallel"), (bytes,), Some(&kwargs))?
+        .extract()
+}
+
+/// Hashes `bytes` as specified for `hash_parallel`, without splitting the work
+pub fn reference_hash_parallel<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    seed: i64,
+    bytes: &[u8],
+    chunk_size: usize,
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let module = PyModule::from_code(
+        py,
+        cr#"
+def hash_parallel(GxHash128, hasher, seed, data, chunk_size):
+    hash128 = GxHash128(seed).hash
+    leaf = lambda chunk: hash128(b"\x00" + hash128(chunk).to_bytes(16, "little"))
+    node = lambda left, right: hash128(b"\x01" + left.to_bytes(16, "little") + right.to_bytes(16, "little"))
+    nodes = [leaf(data[i : i + chunk_size]) for i in range(0, len(data), chunk_size)] or [leaf(b"")]
+
+    while len(nodes) > 1:
+        pairs = zip(nodes[::2], nodes[1::2])
+        nodes = [node(left, right) for left, right in pairs] + (nodes[-1:] if len(nodes) % 2 else [])
+
+    return hasher.hash(nodes[0].to_bytes(16, "little") + len(data).to_bytes(8, "little"))
+"#,
+        c"hash_parallel.py",
+        c"hash_parallel",
+    )?;
+
+    module
+        .call_method1(
+            intern!(py, "hash_parallel"),
+            (py.import_gxhash128()?, obj, seed, PyBytes::new(py, bytes), chunk_size),
+        )?
+        .extract()
+}
+
+pub fn call_hash_file_async<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, path: &std::path::Path) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    py.import_asyncio()?
+        .call_method1(
+            intern!(py, "run"),
+            (obj.call_method1(intern!(py, "hash_file_async"), (path,))?,),
+        )?
+        .extract()
+}
+
+fn chunks_module(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+    PyModule::from_code(
+        py,
+        cr"
+async def chunks(sleep, items):
+    for item in items:
+        await sleep(0)
+        yield item
+
+async def hash_stream_async(sleep, hasher, items):
+    return await hasher.hash_stream_async(chunks(sleep, items))
+",
+        c"chunks.py",
+        c"chunks",
+    )
+}
+
+pub fn call_hash_stream_async<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let asyncio = py.import_asyncio()?;
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    let coroutine = chunks_module(py)?.call_method1(
+        intern!(py, "hash_stream_async"),
+        (asyncio.getattr(intern!(py, "sleep"))?, obj, items),
+    )?;
+
+    asyncio.call_method1(intern!(py, "run"), (coroutine,))?.extract()
+}
+
+pub fn call_hash_stream_async_trio<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let trio = py.import_trio()?;
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    let hash_stream_async = chunks_module(py)?.getattr(intern!(py, "hash_stream_async"))?;
+
+    trio.call_method1(
+        intern!(py, "run"),
+        (hash_stream_async, trio.getattr(intern!(py, "sleep"))?, obj, items),
+    )?
+    .extract()
+}
+
+/// Hashes the concatenated `items` with a streaming hashlib object, which `hash_stream_async` must agree with
+pub fn reference_hash_stream<'py, T>(
+    py: Python<'py>,
+    constructor: &Bound<'py, PyAny>,
+    seed: i64,
+    items: &[Vec<u8>],
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "seed"), seed)?;
+    kwargs.set_item(intern!(py, "streaming"), true)?;
+    let hasher = constructor.call((), Some(&kwargs))?;
+
+    for item in items {
+        hasher.call_method1(intern!(py, "update"), (PyBytes::new(py, item),))?;
+    }
+
+    let digest = hasher.call_method0(intern!(py, "digest"))?;
+
+    py.get_type::<pyo3::types::PyInt>()
+        .call_method1(intern!(py, "from_bytes"), (digest, intern!(py, "little")))?
+        .extract()
+}
+
+pub fn call_hash_coalesced<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    let module = PyModule::from_code(
+        py,
+        cr"
+import asyncio
+
+async def hash_coalesced(hasher, items):
+    return await asyncio.gather(*(hasher.hash_coalesced(item) for item in items))
+",
+        c"hash_coalesced.py",
+        c"hash_coalesced",
+    )?;
+
+    py.import_asyncio()?
+        .getattr(intern!(py, "run"))?
+        .call1((module.call_method1(intern!(py, "hash_coalesced"), (obj, items))?,))?
+        .extract()
+}
+
+pub fn call_hash_async_with_timeout<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    bytes: &[u8],
+    timeout: f64,
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "timeout"), timeout)?;
+
+    py.import_asyncio()?
+        .getattr(intern!(py, "run"))?
+        .call1((obj.call_method(intern!(py, "hash_async"), (bytes,), Some(&kwargs))?,))?
+        .extract()
+}
+
+pub fn call_hash_async_offloads<'py>(
+    py: Python<'py>,
+    class: &Bound<'py, PyAny>,
+    kwargs: &Bound<'py, PyDict>,
+    bytes: &[u8],
+) -> PyResult<bool> {
+    let spawned = Box::leak(Box::new(AtomicUsize::new(0)));
+    let kwargs = kwargs.copy()?;
+    kwargs.set_item("executor", foreign_executor(py, spawned, thread_spawn_blocking)?)?;
+    call_hash_async::<u128>(py, &class.call((42,), Some(&kwargs))?, bytes)?;
+
+    Ok(spawned.load(Ordering::Relaxed) > 0)
+}
+
+/// Returns whether `hash_async` hashed `bytes` on another thread than the event loop, which reports its progress
+pub fn call_hash_async_on_worker<'py>(py: Python<'py>, obj: &Bound<'py, PyAny>, bytes: &[u8]) -> PyResult<bool> {
+    let module = PyModule::from_code(
+        py,
+        cr"
+import asyncio
+import threading
+
+def hash_async_on_worker(hasher, data):
+    threads = []
+    asyncio.run(hasher.hash_async(data, progress=lambda _: threads.append(threading.get_ident())))
+    return threads != [threading.get_ident()]
+",
+        c"hash_async_on_worker.py",
+        c"hash_async_on_worker",
+    )?;
+
+    module
+        .call_method1(intern!(py, "hash_async_on_worker"), (obj, PyBytes::new(py, bytes)))?
+        .extract()
+}
+
+pub fn call_hash_many_async<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+
+    py.import_asyncio()?
+        .getattr(intern!(py, "run"))?
+        .call1((obj.call_method1(intern!(py, "hash_many_async"), (items,))?,))?
+        .extract()
+}
+
+pub fn call_hash_many_async_trio<'py, T>(py: Python<'py>, obj: &Bound<'py, PyAny>, items: &[Vec<u8>]) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+
+    py.import_trio()?
+        .getattr(intern!(py, "run"))?
+        .call1((obj.getattr(intern!(py, "hash_many_async"))?, items))?
+        .extract()
+}
+
+pub fn call_hash_async_trio_concurrently<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    items: &[Vec<u8>],
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    let module = PyModule::from_code(
+        py,
+        cr"
+import trio
+
+async def hash_concurrently(hasher, items):
+    results = [None] * len(items)
+
+    async def hash_into(index, item):
+        results[index] = await hasher.hash_async(item)
+
+    async with trio.open_nursery() as nursery:
+        for index, item in enumerate(items):
+            nursery.start_soon(hash_into, index, item)
+
+    return results
+",
+        c"hash_concurrently.py",
+        c"hash_concurrently",
+    )?;
+
+    py.import_trio()?
+        .getattr(intern!(py, "run"))?
+        .call1((module.getattr(intern!(py, "hash_concurrently"))?, obj, items))?
+        .extract()
+}
+
+pub fn call_hash_many_async_with_timeout<'py, T>(
+    py: Python<'py>,
+    obj: &Bound<'py, PyAny>,
+    items: &[Vec<u8>],
+    timeout: f64,
+) -> PyResult<T>
+where
+    for<'s> T: pyo3::FromPyObject<'s, 's, Error = pyo3::PyErr>,
+{
+    let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "timeout"), timeout)?;
+
+    py.import_asyncio()?
+        .getattr(intern!(py, "run"))?
+        .call1((obj.call_method(intern!(py, "hash_many_async"), (items,), Some(&kwargs))?,))?
+        .extract()
+}
+
+#[cfg(unix)]
+pub fn run_in_child<'py>(py: Python<'py>, child: impl FnOnce(Python<'py>) -> PyResult<()>) -> PyResult<i32> {
+    let os = py.import(intern!(py, "os"))?;
+    let pid = os.call_method0(intern!(py, "fork"))?.extract::<i32>()?;
+
+    if pid == 0 {
+        let status = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| child(py))) {
+            Ok(Ok(())) => 0,
+            Ok(Err(error)) => {
+                error.print(py);
+                1
+            }
+            Err(_) => 1,
+        };
+
+        os.call_method1(intern!(py, "_exit"), (status,))?;
+    }
+
+    let (_, status) = os
+        .call_method1(intern!(py, "waitpid"), (pid, 0))?
+        .extract::<(i32, i32)>()?;
+
+    os.call_method1(intern!(py, "waitstatus_to_exitcode"), (status,))?
+        .extract()
+}
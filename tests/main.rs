@@ -1,3 +1,4 @@
 mod helpers;
 mod test_core;
+mod test_executor;
 mod test_hashlib;
@@ -3,12 +3,50 @@
 use crate::helpers::PythonExt;
 use crate::helpers::call_hash;
 use crate::helpers::call_hash_async;
+use crate::helpers::call_hash_async_anyio;
+use crate::helpers::call_hash_async_offloads;
+use crate::helpers::call_hash_async_on_worker;
+use crate::helpers::call_hash_async_trio;
+use crate::helpers::call_hash_async_trio_concurrently;
+use crate::helpers::call_hash_async_with_timeout;
+use crate::helpers::call_hash_coalesced;
+use crate::helpers::call_hash_file_async;
+use crate::helpers::call_hash_many;
+use crate::helpers::call_hash_many_async;
+use crate::helpers::call_hash_many_async_trio;
+use crate::helpers::call_hash_many_async_with_timeout;
+use crate::helpers::call_hash_parallel;
+use crate::helpers::call_hash_stream_async;
+use crate::helpers::call_hash_stream_async_trio;
+use crate::helpers::call_hash_submit;
+use crate::helpers::foreign_executor;
+use crate::helpers::reference_hash_parallel;
+use crate::helpers::reference_hash_stream;
+#[cfg(unix)]
+use crate::helpers::run_in_child;
+use crate::helpers::thread_spawn_blocking;
 use crate::pytest;
 use gxhash::gxhash_py;
+use pyo3::Bound;
+use pyo3::PyAny;
 use pyo3::PyResult;
+use pyo3::Python;
+use pyo3::intern;
 use pyo3::types::IntoPyDict;
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
+use pyo3::types::PyInt;
+use pyo3::types::PyList;
+use pyo3::types::PyMemoryView;
+use pyo3::types::PyModule;
+use pyo3::types::PySlice;
+use pyo3::types::PyString;
 use quickcheck_macros::quickcheck;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
 #[test]
 fn test_import_gxhash() -> PyResult<()> {
@@ -277,6 +315,1427 @@ fn test_gxhash128_hash_async_large_input(seed: i64) -> PyResult<()> {
     })
 }
 
+#[quickcheck]
+fn test_gxhash32_hash_async_offload_threshold(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash32 = py.import_gxhash32()?;
+        let hashers = [
+            gxhash32.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u32>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async::<u32>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async::<Vec<u32>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_async_offload_threshold(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let hashers = [
+            gxhash64.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u64>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async::<u64>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async::<Vec<u64>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_async_offload_threshold(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash128 = py.import_gxhash128()?;
+        let hashers = [
+            gxhash128.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u128>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async::<u128>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async::<Vec<u128>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_async_trio(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash32 = py.import_gxhash32()?;
+        let hashers = [
+            gxhash32.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u32>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async_trio::<u32>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async_trio::<Vec<u32>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_async_trio(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let hashers = [
+            gxhash64.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u64>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async_trio::<u64>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async_trio::<Vec<u64>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_async_trio(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash128 = py.import_gxhash128()?;
+        let hashers = [
+            gxhash128.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u128>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async_trio::<u128>(py, hasher, &bytes)?, expected);
+            assert_eq!(
+                call_hash_many_async_trio::<Vec<u128>>(py, hasher, std::slice::from_ref(&bytes))?,
+                vec![expected]
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_async_anyio(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let hashers = [
+            gxhash64.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u64>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_async_anyio::<u64>(py, hasher, &bytes, "asyncio")?, expected);
+            assert_eq!(call_hash_async_anyio::<u64>(py, hasher, &bytes, "trio")?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_file_async(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let path = std::env::temp_dir().join(format!("gxhash32-hash-file-async-{}", std::process::id()));
+        std::fs::write(&path, &bytes)?;
+
+        assert_eq!(
+            call_hash_file_async::<u32>(py, &hasher, &path)?,
+            call_hash::<u32>(py, &hasher, &bytes)?
+        );
+        std::fs::remove_file(&path)?;
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_file_async(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let path = std::env::temp_dir().join(format!("gxhash64-hash-file-async-{}", std::process::id()));
+        std::fs::write(&path, &bytes)?;
+
+        assert_eq!(
+            call_hash_file_async::<u64>(py, &hasher, &path)?,
+            call_hash::<u64>(py, &hasher, &bytes)?
+        );
+        std::fs::remove_file(&path)?;
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_file_async(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let path = std::env::temp_dir().join(format!("gxhash128-hash-file-async-{}", std::process::id()));
+        std::fs::write(&path, &bytes)?;
+
+        assert_eq!(
+            call_hash_file_async::<u128>(py, &hasher, &path)?,
+            call_hash::<u128>(py, &hasher, &bytes)?
+        );
+        std::fs::remove_file(&path)?;
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_file_async_mapped() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let path = std::env::temp_dir().join(format!("gxhash64-hash-file-async-mapped-{}", std::process::id()));
+        let bytes = (0..(3 << 20) + 5).map(|i: u32| i.to_le_bytes()[1]).collect::<Vec<_>>();
+        std::fs::write(&path, &bytes)?;
+
+        let pathlib_path = py.import("pathlib")?.getattr("Path")?.call1((&path,))?;
+        let coroutine = hasher.call_method1("hash_file_async", (pathlib_path,))?;
+        let hash = py
+            .import_asyncio()?
+            .call_method1("run", (coroutine,))?
+            .extract::<u64>()?;
+
+        assert_eq!(hash, call_hash::<u64>(py, &hasher, &bytes)?);
+        assert_eq!(call_hash_file_async::<u64>(py, &hasher, &path)?, hash);
+
+        // with a progress callable, the file is read in windows rather than mapped
+        let reports = PyList::empty(py);
+        let kwargs = [("progress_interval", 1 << 20)].into_py_dict(py)?;
+        kwargs.set_item("progress", reports.getattr("append")?)?;
+        let coroutine = hasher.call_method("hash_file_async", (&path,), Some(&kwargs))?;
+
+        assert_eq!(
+            py.import_asyncio()?
+                .call_method1("run", (coroutine,))?
+                .extract::<u64>()?,
+            hash
+        );
+        assert_eq!(
+            reports.extract::<Vec<usize>>()?,
+            vec![1 << 20, 2 << 20, 3 << 20, bytes.len()]
+        );
+        std::fs::remove_file(&path)?;
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_file_async_errors() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let missing = std::env::temp_dir().join(format!("gxhash64-hash-file-async-missing-{}", std::process::id()));
+        let error = call_hash_file_async::<u64>(py, &hasher, &missing).unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyFileNotFoundError>(py));
+        assert!(call_hash_file_async::<u64>(py, &hasher, &std::env::temp_dir()).is_err());
+
+        let coroutine = hasher.call_method1("hash_file_async", (42,))?;
+        let error = py.import_asyncio()?.call_method1("run", (coroutine,)).unwrap_err();
+        assert!(error.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_file_async_progress() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let path = std::env::temp_dir().join(format!("gxhash64-hash-file-async-progress-{}", std::process::id()));
+        let bytes = (0..100_000).map(|i: u32| i.to_le_bytes()[0]).collect::<Vec<_>>();
+        std::fs::write(&path, &bytes)?;
+
+        let reports = PyList::empty(py);
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("progress", reports.getattr("append")?)?;
+        kwargs.set_item("progress_interval", 4096)?;
+        let coroutine = hasher.call_method("hash_file_async", (&path,), Some(&kwargs))?;
+        let hash = py
+            .import_asyncio()?
+            .call_method1("run", (coroutine,))?
+            .extract::<u64>()?;
+        let expected = (1..=24).map(|i| i * 4096).chain([100_000]).collect::<Vec<usize>>();
+
+        assert_eq!(hash, call_hash::<u64>(py, &hasher, &bytes)?);
+        assert_eq!(reports.extract::<Vec<usize>>()?, expected);
+
+        let failing = PyModule::from_code(
+            py,
+            c"def failing(processed):\n    raise ValueError(processed)",
+            c"failing.py",
+            c"failing",
+        )?;
+        let kwargs = [("progress", failing.getattr("failing")?)].into_py_dict(py)?;
+        let coroutine = hasher.call_method("hash_file_async", (&path,), Some(&kwargs))?;
+        let error = py.import_asyncio()?.call_method1("run", (coroutine,)).unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        std::fs::remove_file(&path)?;
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_progress() -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let bytes = vec![7u8; 10_000];
+
+        for threshold in ["always", "never"] {
+            let hasher = gxhash64.call((42,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?;
+            let reports = PyList::empty(py);
+            let kwargs = [("progress", reports.getattr("append")?)].into_py_dict(py)?;
+            let coroutine = hasher.call_method("hash_async", (PyBytes::new(py, &bytes),), Some(&kwargs))?;
+            let hash = py
+                .import_asyncio()?
+                .call_method1("run", (coroutine,))?
+                .extract::<u64>()?;
+
+            assert_eq!(hash, call_hash::<u64>(py, &hasher, &bytes)?);
+            assert_eq!(reports.extract::<Vec<usize>>()?, vec![bytes.len()]);
+        }
+
+        let hasher = gxhash64.call1((42,))?;
+        let kwargs = [("progress_interval", 0)].into_py_dict(py)?;
+        let coroutine = hasher.call_method("hash_async", (PyBytes::new(py, &bytes),), Some(&kwargs))?;
+        let error = py.import_asyncio()?.call_method1("run", (coroutine,)).unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_stream_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u32>(py, &py.import_hashlib_gxhash32()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async::<u32>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_stream_async_trio(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u32>(py, &py.import_hashlib_gxhash32()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async_trio::<u32>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_stream_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u64>(py, &py.import_hashlib_gxhash64()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async::<u64>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_stream_async_trio(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u64>(py, &py.import_hashlib_gxhash64()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async_trio::<u64>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_stream_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u128>(py, &py.import_hashlib_gxhash128()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async::<u128>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_stream_async_trio(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let expected = reference_hash_stream::<u128>(py, &py.import_hashlib_gxhash128()?, seed, &items)?;
+
+        assert_eq!(call_hash_stream_async_trio::<u128>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_stream_async_split() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let bytes = (0..(5 << 20) + 7).map(|i: u32| i.to_le_bytes()[0]).collect::<Vec<_>>();
+        let (head, tail) = bytes.split_at(100_003);
+        let expected = call_hash_stream_async::<u64>(py, &hasher, std::slice::from_ref(&bytes))?;
+
+        assert_eq!(
+            call_hash_stream_async::<u64>(py, &hasher, &[head.to_vec(), Vec::new(), tail.to_vec()])?,
+            expected
+        );
+        assert_ne!(expected, call_hash::<u64>(py, &hasher, &bytes)?);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_stream_async_errors() -> PyResult<()> {
+    pytest!(py, {
+        let module = PyModule::from_code(
+            py,
+            cr#"
+import asyncio
+
+class Failure(Exception):
+    pass
+
+async def failing():
+    yield b"hello"
+    await asyncio.sleep(0)
+    raise Failure
+
+async def invalid():
+    yield "hello"
+
+async def reuse(hasher):
+    coroutine = hasher.hash_stream_async(invalid())
+
+    try:
+        await coroutine
+    except TypeError:
+        pass
+
+    try:
+        await coroutine
+    except RuntimeError as e:
+        return str(e)
+
+async def cancelled(hasher):
+    event = asyncio.Event()
+
+    async def forever():
+        try:
+            yield b"hello"
+            await event.wait()
+        finally:
+            event.set()
+
+    task = asyncio.ensure_future(hasher.hash_stream_async(forever()))
+    await asyncio.sleep(0.01)
+    task.cancel()
+
+    try:
+        await task
+    except asyncio.CancelledError:
+        return event.is_set()
+"#,
+            c"errors.py",
+            c"errors",
+        )?;
+        let asyncio = py.import_asyncio()?;
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let run = |coroutine| asyncio.call_method1("run", (coroutine,));
+
+        let failing = hasher.call_method1("hash_stream_async", (module.call_method0("failing")?,))?;
+        assert!(run(failing).unwrap_err().is_instance(py, &module.getattr("Failure")?));
+
+        let invalid = hasher.call_method1("hash_stream_async", (module.call_method0("invalid")?,))?;
+        assert!(
+            run(invalid)
+                .unwrap_err()
+                .is_instance_of::<pyo3::exceptions::PyTypeError>(py)
+        );
+
+        let not_iterable = hasher.call_method1("hash_stream_async", (b"hello",))?;
+        assert!(run(not_iterable).is_err());
+
+        assert_eq!(
+            run(module.call_method1("reuse", (&hasher,))?)?.extract::<String>()?,
+            "cannot reuse already awaited coroutine"
+        );
+        assert!(run(module.call_method1("cancelled", (&hasher,))?)?.extract::<bool>()?);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_async_trio_concurrently(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher_kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((seed,), Some(&hasher_kwargs))?;
+
+        assert_eq!(
+            call_hash_async_trio_concurrently::<Vec<u64>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_trio_foreign_executor() -> PyResult<()> {
+    pytest!(py, {
+        let spawned = Box::leak(Box::new(AtomicUsize::new(0)));
+        let executor = foreign_executor(py, spawned, thread_spawn_blocking)?;
+        let hasher_kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        hasher_kwargs.set_item("executor", executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&hasher_kwargs))?;
+        let bytes = vec![0u8; 1 << 20];
+
+        assert_eq!(
+            call_hash_async_trio::<u64>(py, &hasher, &bytes)?,
+            call_hash::<u64>(py, &hasher, &bytes)?
+        );
+        assert!(spawned.load(Ordering::Relaxed) > 0);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_submit(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash32 = py.import_gxhash32()?;
+        let hashers = [
+            gxhash32.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash32.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u32>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_submit::<u32>(py, hasher, &bytes)?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_submit(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let hashers = [
+            gxhash64.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash64.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u64>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_submit::<u64>(py, hasher, &bytes)?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_submit(seed: i64, bytes: Vec<u8>, threshold: u16) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash128 = py.import_gxhash128()?;
+        let hashers = [
+            gxhash128.call((seed,), Some(&[("offload_threshold", threshold)].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "always")].into_py_dict(py)?))?,
+            gxhash128.call((seed,), Some(&[("offload_threshold", "never")].into_py_dict(py)?))?,
+        ];
+        let expected = call_hash::<u128>(py, &hashers[0], &bytes)?;
+
+        for hasher in &hashers {
+            assert_eq!(call_hash_submit::<u128>(py, hasher, &bytes)?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_submit_from_threads(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher_kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((seed,), Some(&hasher_kwargs))?;
+        let items = items.iter().map(|bytes| PyBytes::new(py, bytes)).collect::<Vec<_>>();
+        let hash_threaded = PyModule::from_code(
+            py,
+            cr"
+from concurrent.futures import ThreadPoolExecutor
+
+def hash_threaded(hasher, items):
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        futures = list(pool.map(hasher.hash_submit, items))
+
+    return [future.result() for future in futures]
+",
+            c"hash_threaded.py",
+            c"hash_threaded",
+        )?
+        .getattr(intern!(py, "hash_threaded"))?;
+
+        assert_eq!(
+            hash_threaded.call1((&hasher, &items))?.extract::<Vec<u64>>()?,
+            hasher
+                .call_method1(intern!(py, "hash_many"), (&items,))?
+                .extract::<Vec<u64>>()?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_parallel(seed: i64, bytes: Vec<u8>, repeat: u8, chunk_size: u8) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let bytes = bytes.repeat(usize::from(repeat));
+        let chunk_size = 4096 + usize::from(chunk_size);
+
+        assert_eq!(
+            call_hash_parallel::<u32>(py, &hasher, &bytes, chunk_size)?,
+            reference_hash_parallel::<u32>(py, &hasher, seed, &bytes, chunk_size)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_parallel(seed: i64, bytes: Vec<u8>, repeat: u8, chunk_size: u8) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let bytes = bytes.repeat(usize::from(repeat));
+        let chunk_size = 4096 + usize::from(chunk_size);
+
+        assert_eq!(
+            call_hash_parallel::<u64>(py, &hasher, &bytes, chunk_size)?,
+            reference_hash_parallel::<u64>(py, &hasher, seed, &bytes, chunk_size)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_parallel(seed: i64, bytes: Vec<u8>, repeat: u8, chunk_size: u8) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let bytes = bytes.repeat(usize::from(repeat));
+        let chunk_size = 4096 + usize::from(chunk_size);
+
+        assert_eq!(
+            call_hash_parallel::<u128>(py, &hasher, &bytes, chunk_size)?,
+            reference_hash_parallel::<u128>(py, &hasher, seed, &bytes, chunk_size)?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash128_hash_parallel_thread_counts() -> PyResult<()> {
+    pytest!(py, {
+        let bytes = (0..1 << 20)
+            .map(|i: u32| i.to_le_bytes()[0] ^ (i >> 8).to_le_bytes()[0])
+            .collect::<Vec<_>>();
+        let managed = py.import_gxhash128()?.call1((42,))?;
+        let expected = reference_hash_parallel::<u128>(py, &managed, 42, &bytes, 4096)?;
+        let spawned = Box::leak(Box::new(AtomicUsize::new(0)));
+        let hasher_kwargs = PyDict::new(py);
+        hasher_kwargs.set_item("executor", foreign_executor(py, spawned, thread_spawn_blocking)?)?;
+        let foreign = py.import_gxhash128()?.call((42,), Some(&hasher_kwargs))?;
+
+        assert_eq!(call_hash_parallel::<u128>(py, &managed, &bytes, 4096)?, expected);
+        assert_eq!(call_hash_parallel::<u128>(py, &foreign, &bytes, 4096)?, expected);
+        assert!(spawned.load(Ordering::Relaxed) > 1);
+
+        #[cfg(unix)]
+        {
+            let status = run_in_child(py, |py| {
+                py.import_gxhash()?.call_method(
+                    intern!(py, "configure_runtime"),
+                    (),
+                    Some(&[("worker_threads", 3)].into_py_dict(py)?),
+                )?;
+
+                let hasher = py.import_gxhash128()?.call1((42,))?;
+                assert_eq!(call_hash_parallel::<u128>(py, &hasher, &bytes, 4096)?, expected);
+                Ok(())
+            })?;
+
+            assert_eq!(status, 0);
+        }
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_parallel_invalid_chunk_size() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        for chunk_size in [0, 4095] {
+            let error = call_hash_parallel::<u64>(py, &hasher, b"hello", chunk_size).unwrap_err();
+            assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        }
+
+        assert_eq!(
+            call_hash_parallel::<u64>(py, &hasher, b"hello", 4096)?,
+            reference_hash_parallel::<u64>(py, &hasher, 42, b"hello", 4096)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_coalesced(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher_kwargs = [("seed", seed)].into_py_dict(py)?;
+        let hasher = py.import_gxhash32()?.call((), Some(&hasher_kwargs))?;
+
+        assert_eq!(
+            call_hash_coalesced::<Vec<u32>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u32>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_coalesced(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher_kwargs = [("seed", seed)].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((), Some(&hasher_kwargs))?;
+
+        assert_eq!(
+            call_hash_coalesced::<Vec<u64>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_coalesced(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher_kwargs = [("seed", seed)].into_py_dict(py)?;
+        let hasher = py.import_gxhash128()?.call((), Some(&hasher_kwargs))?;
+
+        assert_eq!(
+            call_hash_coalesced::<Vec<u128>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u128>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_coalesced_within_one_tick() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let module = PyModule::from_code(
+            py,
+            cr"
+import asyncio
+
+async def coalesce(hasher):
+    futures = [hasher.hash_coalesced(bytes([item])) for item in range(8)]
+    futures[0].cancel()
+    pending = not any(future.done() for future in futures[1:])
+    await asyncio.sleep(0)
+
+    return pending and all(future.done() for future in futures)
+",
+            c"coalesce.py",
+            c"coalesce",
+        )?;
+
+        assert!(
+            py.import_asyncio()?
+                .getattr(intern!(py, "run"))?
+                .call1((module.call_method1(intern!(py, "coalesce"), (&hasher,))?,))?
+                .extract::<bool>()?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_coalesced_without_event_loop() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let error = hasher
+            .call_method1(intern!(py, "hash_coalesced"), (b"hello",))
+            .unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_default_offload_threshold() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let offload_threshold = py
+            .import_gxhash()?
+            .call_method0(intern!(py, "offload_threshold"))?
+            .extract::<usize>()?;
+
+        assert!(!call_hash_async_on_worker(
+            py,
+            &hasher,
+            &vec![0u8; offload_threshold - 1]
+        )?);
+        assert!(call_hash_async_on_worker(py, &hasher, &vec![0u8; offload_threshold])?);
+    })
+}
+
+#[test]
+fn test_calibrate_offload_threshold() -> PyResult<()> {
+    pytest!(py, {
+        let gxhash = py.import_gxhash()?;
+        let calibrated = gxhash.call_method0(intern!(py, "calibrate"))?.extract::<usize>()?;
+        let offload_threshold = gxhash
+            .call_method0(intern!(py, "offload_threshold"))?
+            .extract::<usize>()?;
+
+        assert!((4 << 10..=64 << 20).contains(&calibrated));
+        assert_eq!(offload_threshold, calibrated);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_custom_offload_threshold() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", 1 << 20)].into_py_dict(py)?;
+        let gxhash64 = py.import_gxhash64()?;
+
+        assert!(!call_hash_async_offloads(
+            py,
+            &gxhash64,
+            &kwargs,
+            &vec![0u8; (1 << 20) - 1]
+        )?);
+        assert!(call_hash_async_offloads(py, &gxhash64, &kwargs, &vec![0u8; 1 << 20])?);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_always_offload() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let gxhash64 = py.import_gxhash64()?;
+
+        assert!(call_hash_async_offloads(py, &gxhash64, &kwargs, b"hello")?);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_never_offload() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "never")].into_py_dict(py)?;
+        let gxhash64 = py.import_gxhash64()?;
+
+        assert!(!call_hash_async_offloads(py, &gxhash64, &kwargs, &vec![0u8; 16 << 20])?);
+    })
+}
+
+#[test]
+fn test_gxhash64_invalid_offload_threshold() -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+
+        for offload_threshold in [PyInt::new(py, -1).into_any(), PyString::new(py, "sometimes").into_any()] {
+            let kwargs = [("offload_threshold", offload_threshold)].into_py_dict(py)?;
+            let error = gxhash64.call((42,), Some(&kwargs)).unwrap_err();
+
+            assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        }
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_timeout() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let items = vec![b"hello".to_vec(); 8];
+
+        assert_eq!(
+            call_hash_async_with_timeout::<u64>(py, &hasher, b"hello", 60.0)?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        assert_eq!(
+            call_hash_many_async_with_timeout::<Vec<u64>>(py, &hasher, &items, 60.0)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash64_invalid_timeout() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+
+        for timeout in [-1.0, f64::NAN, f64::INFINITY] {
+            let error = call_hash_async_with_timeout::<u64>(py, &hasher, b"hello", timeout).unwrap_err();
+
+            assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        }
+    })
+}
+
+#[test]
+fn test_deprecated_runtime_attribute() -> PyResult<()> {
+    pytest!(py, {
+        let module = PyModule::from_code(
+            py,
+            cr#"
+import warnings
+
+def get_runtime(module):
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        runtime = module.runtime
+
+    return runtime, [warning.category for warning in caught]
+"#,
+            c"deprecated.py",
+            c"deprecated",
+        )?;
+        let gxhash = py.import_gxhash()?;
+        let (runtime, categories) = module
+            .call_method1(intern!(py, "get_runtime"), (&gxhash,))?
+            .extract::<(Bound<'_, PyAny>, Vec<Bound<'_, PyAny>>)>()?;
+        let missing_error = gxhash.getattr(intern!(py, "missing")).unwrap_err();
+
+        assert!(!runtime.is_none());
+        assert!(
+            categories
+                .iter()
+                .any(|category| category.is(py.get_type::<pyo3::exceptions::PyDeprecationWarning>()))
+        );
+        assert!(missing_error.is_instance_of::<pyo3::exceptions::PyAttributeError>(py));
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_runtime_after_fork() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let expected = call_hash_async::<u64>(py, &hasher, b"hello")?;
+        py.detach(|| std::thread::sleep(std::time::Duration::from_millis(100)));
+
+        let status = run_in_child(py, |py| {
+            assert_eq!(call_hash_async::<u64>(py, &hasher, b"hello")?, expected);
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shutdown_runtime() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            let expected = call_hash::<u64>(py, &hasher, b"hello")?;
+
+            assert_eq!(call_hash_async::<u64>(py, &hasher, b"hello")?, expected);
+            gxhash.call_method0(intern!(py, "shutdown_runtime"))?;
+            gxhash.call_method0(intern!(py, "shutdown_runtime"))?;
+            assert_eq!(call_hash_async::<u64>(py, &hasher, b"hello")?, expected);
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_configure_runtime() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            let configure_runtime = gxhash.getattr(intern!(py, "configure_runtime"))?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            let items = vec![b"hello".to_vec(); 8];
+            let expected = call_hash_many::<Vec<u64>>(py, &hasher, &items)?;
+
+            configure_runtime.call((), Some(&[("worker_threads", 2)].into_py_dict(py)?))?;
+            assert_eq!(call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?, expected);
+
+            let running_error = configure_runtime
+                .call((), Some(&[("worker_threads", 4)].into_py_dict(py)?))
+                .unwrap_err();
+            let zero_error = configure_runtime
+                .call((), Some(&[("worker_threads", 0)].into_py_dict(py)?))
+                .unwrap_err();
+
+            gxhash.call_method0(intern!(py, "shutdown_runtime"))?;
+            configure_runtime.call0()?;
+
+            assert!(running_error.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+            assert!(zero_error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert_eq!(call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?, expected);
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_runtime_worker_threads_env() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let environ = py.import(intern!(py, "os"))?.getattr(intern!(py, "environ"))?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+
+            environ.set_item("GXHASH_WORKER_THREADS", "zero")?;
+            let error = call_hash_async::<u64>(py, &hasher, b"hello").unwrap_err();
+            environ.set_item("GXHASH_WORKER_THREADS", "2")?;
+
+            assert!(error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert_eq!(
+                call_hash_async::<u64>(py, &hasher, b"hello")?,
+                call_hash::<u64>(py, &hasher, b"hello")?
+            );
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_hash_does_not_start_runtime() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            let hasher = py.import_gxhash64()?.call1((42,))?;
+            let input = vec![0u8; 16 << 20];
+
+            call_hash::<u64>(py, &hasher, &input)?;
+            py.import(intern!(py, "time"))?
+                .call_method1(intern!(py, "sleep"), (0.5,))?;
+            gxhash.call_method0(intern!(py, "configure_runtime"))?;
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_large_input_releases_gil() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "never")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let input = vec![0u8; 16 << 20];
+        let ticks = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            let counter = scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    Python::attach(|_| ticks.fetch_add(1, Ordering::Relaxed));
+                }
+            });
+
+            py.detach(|| {
+                while ticks.load(Ordering::Relaxed) == 0 {
+                    std::thread::yield_now();
+                }
+            });
+
+            let before = ticks.load(Ordering::Relaxed);
+            let result = hasher.call_method1(intern!(py, "hash"), (PyBytes::new(py, &input),));
+            let after = ticks.load(Ordering::Relaxed);
+            stop.store(true, Ordering::Relaxed);
+            py.detach(|| counter.join()).unwrap();
+
+            assert!(after > before);
+            assert_eq!(result?.extract::<u64>()?, call_hash_async::<u64>(py, &hasher, &input)?);
+            Ok::<_, pyo3::PyErr>(())
+        })?;
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let array = py
+            .import(intern!(py, "array"))?
+            .call_method1(intern!(py, "array"), ("B", &bytearray))?;
+        let expected = call_hash::<u32>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any(), &array] {
+            let result = hasher.call_method1(intern!(py, "hash"), (buffer,))?.extract::<u32>()?;
+            assert_eq!(result, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let array = py
+            .import(intern!(py, "array"))?
+            .call_method1(intern!(py, "array"), ("B", &bytearray))?;
+        let expected = call_hash::<u64>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any(), &array] {
+            let result = hasher.call_method1(intern!(py, "hash"), (buffer,))?.extract::<u64>()?;
+            assert_eq!(result, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let array = py
+            .import(intern!(py, "array"))?
+            .call_method1(intern!(py, "array"), ("B", &bytearray))?;
+        let expected = call_hash::<u128>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any(), &array] {
+            let result = hasher.call_method1(intern!(py, "hash"), (buffer,))?.extract::<u128>()?;
+            assert_eq!(result, expected);
+        }
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_mmap() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let mmap = py
+            .import(intern!(py, "mmap"))?
+            .call_method1(intern!(py, "mmap"), (-1, 5))?;
+        mmap.call_method1(intern!(py, "write"), (b"hello",))?;
+
+        let result = hasher.call_method1(intern!(py, "hash"), (&mmap,))?.extract::<u64>()?;
+        mmap.call_method0(intern!(py, "close"))?;
+
+        assert_eq!(result, 10_922_345_113_571_621_535_u64);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_typed_buffer() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let array = py
+            .import(intern!(py, "array"))?
+            .call_method1(intern!(py, "array"), ("i", [1, 2, 3]))?;
+        let bytes = array.call_method0(intern!(py, "tobytes"))?.extract::<Vec<u8>>()?;
+        let hashlib = py.import_hashlib_gxhash64()?;
+
+        assert_eq!(
+            hasher.call_method1(intern!(py, "hash"), (&array,))?.extract::<u64>()?,
+            call_hash::<u64>(py, &hasher, &bytes)?
+        );
+        assert_eq!(
+            hashlib
+                .call1((&array,))?
+                .call_method0(intern!(py, "digest"))?
+                .extract::<Vec<u8>>()?,
+            hashlib
+                .call1((PyBytes::new(py, &bytes),))?
+                .call_method0(intern!(py, "digest"))?
+                .extract::<Vec<u8>>()?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_async_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let run = py.import_asyncio()?.getattr(intern!(py, "run"))?;
+        let expected = call_hash::<u32>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any()] {
+            let coroutine = hasher.call_method1(intern!(py, "hash_async"), (buffer,))?;
+            assert_eq!(run.call1((coroutine,))?.extract::<u32>()?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_async_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let run = py.import_asyncio()?.getattr(intern!(py, "run"))?;
+        let expected = call_hash::<u64>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any()] {
+            let coroutine = hasher.call_method1(intern!(py, "hash_async"), (buffer,))?;
+            assert_eq!(run.call1((coroutine,))?.extract::<u64>()?, expected);
+        }
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_async_buffers(seed: i64, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let bytearray = PyByteArray::new(py, &bytes);
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let run = py.import_asyncio()?.getattr(intern!(py, "run"))?;
+        let expected = call_hash::<u128>(py, &hasher, &bytes)?;
+
+        for buffer in [bytearray.as_any(), memoryview.as_any()] {
+            let coroutine = hasher.call_method1(intern!(py, "hash_async"), (buffer,))?;
+            assert_eq!(run.call1((coroutine,))?.extract::<u128>()?, expected);
+        }
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_resize_while_hashing() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let input = vec![1u8; 16 << 20];
+        let bytearray = PyByteArray::new(py, &input);
+        let asyncio = py.import_asyncio()?;
+        let event_loop = asyncio.call_method0(intern!(py, "new_event_loop"))?;
+        let coroutine = hasher.call_method1(intern!(py, "hash_async"), (&bytearray,))?;
+        let task = event_loop.call_method1(intern!(py, "create_task"), (coroutine,))?;
+        event_loop.call_method1(
+            intern!(py, "run_until_complete"),
+            (asyncio.call_method1(intern!(py, "sleep"), (0,))?,),
+        )?;
+
+        let resize_error = bytearray.call_method1(intern!(py, "extend"), (b"\x00",)).unwrap_err();
+        let result = event_loop
+            .call_method1(intern!(py, "run_until_complete"), (task,))?
+            .extract::<u64>()?;
+        event_loop.call_method0(intern!(py, "close"))?;
+        bytearray.call_method1(intern!(py, "extend"), (b"\x00",))?;
+
+        assert!(resize_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+        assert_eq!(result, call_hash::<u64>(py, &hasher, &input)?);
+        assert_eq!(bytearray.len()?, input.len() + 1);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_release_while_hashing() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let input = vec![1u8; 16 << 20];
+        let memoryview = PyMemoryView::from(&PyByteArray::new(py, &input))?;
+        let asyncio = py.import_asyncio()?;
+        let event_loop = asyncio.call_method0(intern!(py, "new_event_loop"))?;
+        let coroutine = hasher.call_method1(intern!(py, "hash_async"), (&memoryview,))?;
+        let task = event_loop.call_method1(intern!(py, "create_task"), (coroutine,))?;
+        event_loop.call_method1(
+            intern!(py, "run_until_complete"),
+            (asyncio.call_method1(intern!(py, "sleep"), (0,))?,),
+        )?;
+
+        let release_error = memoryview.call_method0(intern!(py, "release")).unwrap_err();
+        let result = event_loop
+            .call_method1(intern!(py, "run_until_complete"), (task,))?
+            .extract::<u64>()?;
+        event_loop.call_method0(intern!(py, "close"))?;
+        memoryview.call_method0(intern!(py, "release"))?;
+
+        assert!(release_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+        assert_eq!(result, call_hash::<u64>(py, &hasher, &input)?);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_non_contiguous_buffer() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let memoryview = PyMemoryView::from(&PyByteArray::new(py, b"hello"))?;
+        let strided = memoryview.get_item(PySlice::new(py, 0, 5, 2))?;
+        let coroutine = hasher.call_method1(intern!(py, "hash_async"), (&strided,))?;
+        let sync_error = hasher.call_method1(intern!(py, "hash"), (&strided,)).unwrap_err();
+        let async_error = py
+            .import_asyncio()?
+            .call_method1(intern!(py, "run"), (coroutine,))
+            .unwrap_err();
+
+        assert!(sync_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+        assert!(async_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_many(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let expected = items
+            .iter()
+            .map(|bytes| call_hash::<u32>(py, &hasher, bytes))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        assert_eq!(call_hash_many::<Vec<u32>>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_many(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let expected = items
+            .iter()
+            .map(|bytes| call_hash::<u64>(py, &hasher, bytes))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        assert_eq!(call_hash_many::<Vec<u64>>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_many(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let expected = items
+            .iter()
+            .map(|bytes| call_hash::<u128>(py, &hasher, bytes))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        assert_eq!(call_hash_many::<Vec<u128>>(py, &hasher, &items)?, expected);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_many_buffers() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let bytearray = PyByteArray::new(py, b"hello");
+        let memoryview = PyMemoryView::from(&bytearray)?;
+        let result = hasher
+            .call_method1("hash_many", ((b"hello", &bytearray, &memoryview),))?
+            .extract::<Vec<u64>>()?;
+
+        assert_eq!(result, [10_922_345_113_571_621_535_u64; 3]);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_many_empty() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let result = call_hash_many::<Vec<u64>>(py, &hasher, &[])?;
+
+        assert!(result.is_empty());
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_many_invalid_item() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let error = hasher.call_method1("hash_many", ((b"hello", 42),)).unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    })
+}
+
+#[quickcheck]
+fn test_gxhash32_hash_many_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((seed,))?;
+        let result1 = call_hash_many_async::<Vec<u32>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u32>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash64_hash_many_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((seed,))?;
+        let result1 = call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u64>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
+#[quickcheck]
+fn test_gxhash128_hash_many_async(seed: i64, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((seed,))?;
+        let result1 = call_hash_many_async::<Vec<u128>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u128>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
+#[test]
+fn test_gxhash32_hash_many_async_large_input() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash32()?.call1((42,))?;
+        let items = (0..24u8).map(|i| vec![i; usize::from(i) << 15]).collect::<Vec<_>>();
+
+        let result1 = call_hash_many_async::<Vec<u32>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u32>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_many_async_large_input() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let items = (0..24u8).map(|i| vec![i; usize::from(i) << 15]).collect::<Vec<_>>();
+
+        let result1 = call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u64>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
+#[test]
+fn test_gxhash128_hash_many_async_large_input() -> PyResult<()> {
+    pytest!(py, {
+        let hasher = py.import_gxhash128()?.call1((42,))?;
+        let items = (0..24u8).map(|i| vec![i; usize::from(i) << 15]).collect::<Vec<_>>();
+
+        let result1 = call_hash_many_async::<Vec<u128>>(py, &hasher, &items)?;
+        let result2 = call_hash_many::<Vec<u128>>(py, &hasher, &items)?;
+
+        assert_eq!(result1, result2);
+    })
+}
+
 #[test]
 fn test_gxhash32_hash_determinism() -> PyResult<()> {
     pytest!(py, {
@@ -0,0 +1,697 @@
+use crate::helpers::PythonExt;
+use crate::helpers::call_hash;
+use crate::helpers::call_hash_async;
+use crate::helpers::call_hash_async_with_timeout;
+use crate::helpers::call_hash_many;
+use crate::helpers::call_hash_many_async;
+use crate::helpers::call_hash_many_async_with_timeout;
+use crate::helpers::call_hash_parallel;
+use crate::helpers::call_hash_submit;
+use crate::helpers::foreign_executor;
+#[cfg(unix)]
+use crate::helpers::run_in_child;
+use crate::helpers::thread_spawn_blocking;
+use crate::pytest;
+use gxhash::GxHashTask;
+use gxhash::gxhash_py;
+use pyo3::Bound;
+use pyo3::PyAny;
+use pyo3::PyResult;
+use pyo3::Python;
+use pyo3::intern;
+use pyo3::types::IntoPyDict;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyBytes;
+use pyo3::types::PyList;
+use pyo3::types::PyModule;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+unsafe extern "C" fn rejecting_spawn_blocking(_: *mut c_void, _: GxHashTask, _: *mut c_void) -> c_int {
+    -1
+}
+
+unsafe extern "C" fn dropping_spawn_blocking(_: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int {
+    unsafe { task(argument, false) };
+    0
+}
+
+unsafe extern "C" fn joining_spawn_blocking(context: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int {
+    let spawned = unsafe { &*context.cast::<AtomicUsize>() };
+    let argument = argument.expose_provenance();
+    spawned.fetch_add(1, Ordering::Relaxed);
+
+    std::thread::spawn(move || unsafe { task(std::ptr::with_exposed_provenance_mut(argument), true) })
+        .join()
+        .map_or(-1, |()| 0)
+}
+
+type Queue = Mutex<Vec<(GxHashTask, usize)>>;
+
+unsafe extern "C" fn deferring_spawn_blocking(context: *mut c_void, task: GxHashTask, argument: *mut c_void) -> c_int {
+    let queue = unsafe { &*context.cast::<Queue>() };
+    queue.lock().unwrap().push((task, argument.expose_provenance()));
+    0
+}
+
+fn run_deferred(queue: &Queue, run: bool) -> usize {
+    let tasks = std::mem::take(&mut *queue.lock().unwrap());
+
+    for &(task, argument) in &tasks {
+        unsafe { task(std::ptr::with_exposed_provenance_mut(argument), run) };
+    }
+
+    tasks.len()
+}
+
+fn queue_depth_when_saturated<'py>(py: Python<'py>, coroutines: Vec<Bound<'py, PyAny>>) -> PyResult<usize> {
+    let module = PyModule::from_code(
+        py,
+        cr"
+import asyncio
+
+async def saturate(coroutines, queue_depth):
+    tasks = [asyncio.ensure_future(coroutine) for coroutine in coroutines]
+    await asyncio.sleep(0)
+    depth = queue_depth()
+
+    for task in tasks:
+        task.cancel()
+
+    return depth
+",
+        c"saturate.py",
+        c"saturate",
+    )?;
+    let queue_depth = py.import_gxhash()?.getattr(intern!(py, "queue_depth"))?;
+
+    py.import_asyncio()?
+        .getattr(intern!(py, "run"))?
+        .call1((module.call_method1(intern!(py, "saturate"), (coroutines, queue_depth))?,))?
+        .extract()
+}
+
+#[test]
+fn test_gxhash64_exported_executor() -> PyResult<()> {
+    pytest!(py, {
+        let executor = py.import_gxhash()?.call_method0(intern!(py, "executor"))?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let items = vec![b"hello".to_vec(); 8];
+
+        assert_eq!(
+            call_hash_async::<u64>(py, &hasher, b"hello")?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        assert_eq!(
+            call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+    })
+}
+
+#[test]
+fn test_gxhash64_foreign_executor() -> PyResult<()> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &SPAWNED, thread_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let items = vec![b"hello".to_vec(); 8];
+
+        assert_eq!(
+            call_hash_async::<u64>(py, &hasher, b"hello")?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        assert_eq!(SPAWNED.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+        assert_eq!(SPAWNED.load(Ordering::Relaxed), 3);
+    })
+}
+
+#[test]
+fn test_gxhash64_executor_task_does_not_attach() -> PyResult<()> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &SPAWNED, joining_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let items = vec![b"hello".to_vec(); 8];
+
+        assert_eq!(
+            call_hash_async::<u64>(py, &hasher, b"hello")?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        assert_eq!(
+            call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?,
+            call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+        );
+        assert_eq!(SPAWNED.load(Ordering::Relaxed), 3);
+    })
+}
+
+#[test]
+fn test_gxhash64_rejecting_executor() -> PyResult<()> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &SPAWNED, rejecting_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let error = call_hash_async::<u64>(py, &hasher, b"hello").unwrap_err();
+        let submit_error = hasher
+            .call_method1(intern!(py, "hash_submit"), (b"hello",))
+            .unwrap_err();
+
+        assert!(error.matches(py, py.import_gxhash()?.getattr(intern!(py, "GxHashAsyncError"))?)?);
+        assert!(submit_error.matches(py, py.import_gxhash()?.getattr(intern!(py, "GxHashAsyncError"))?)?);
+    })
+}
+
+#[test]
+fn test_gxhash64_dropping_executor() -> PyResult<()> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &SPAWNED, dropping_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let error = call_hash_async::<u64>(py, &hasher, b"hello").unwrap_err();
+
+        assert!(error.matches(py, py.import_gxhash()?.getattr(intern!(py, "GxHashAsyncError"))?)?);
+    })
+}
+
+#[test]
+fn test_gxhash64_invalid_executor() -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_gxhash64()?;
+        let object = py.eval(c"object()", None, None)?;
+        let capsule = py
+            .import(intern!(py, "datetime"))?
+            .getattr(intern!(py, "datetime_CAPI"))?;
+        let object_error = gxhash64
+            .call((42,), Some(&[("executor", object)].into_py_dict(py)?))
+            .unwrap_err();
+        let capsule_error = gxhash64
+            .call((42,), Some(&[("executor", capsule)].into_py_dict(py)?))
+            .unwrap_err();
+
+        assert!(object_error.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        assert!(capsule_error.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    })
+}
+
+#[test]
+fn test_gxhash64_cancelled_task_is_skipped() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let bytes = PyBytes::new(py, &vec![0u8; 16 << 20]);
+        let items = PyList::new(py, [&bytes, &bytes])?;
+        let cancel = PyModule::from_code(
+            py,
+            cr"
+async def cancel(coroutine):
+    coroutine.send(None)
+    coroutine.close()
+",
+            c"cancel.py",
+            c"cancel",
+        )?
+        .getattr(intern!(py, "cancel"))?;
+        let run = py.import_asyncio()?.getattr(intern!(py, "run"))?;
+
+        run.call1((cancel.call1((hasher.call_method1(intern!(py, "hash_async"), (&bytes,))?,))?,))?;
+        run.call1((cancel.call1((hasher.call_method1(intern!(py, "hash_many_async"), (&items,))?,))?,))?;
+
+        let start = Instant::now();
+        assert_eq!(run_deferred(&QUEUE, true), 3);
+        let skipping = start.elapsed();
+
+        let start = Instant::now();
+        hasher.call_method1(intern!(py, "hash"), (&bytes,))?;
+        let hashing = start.elapsed();
+
+        assert!(skipping * 10 < hashing);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_wakes_without_attaching() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let asyncio = py.import_asyncio()?;
+        let event_loop = asyncio.call_method0(intern!(py, "new_event_loop"))?;
+        let coroutine = hasher.call_method1(intern!(py, "hash_async"), (PyBytes::new(py, b"hello"),))?;
+        let task = event_loop.call_method1(intern!(py, "create_task"), (coroutine,))?;
+        event_loop.call_method1(
+            intern!(py, "run_until_complete"),
+            (asyncio.call_method1(intern!(py, "sleep"), (0,))?,),
+        )?;
+
+        // this thread holds the GIL meanwhile, so the worker only returns if waking the coroutine does not attach
+        let worker = std::thread::spawn(|| run_deferred(&QUEUE, true));
+        let start = Instant::now();
+
+        while !worker.is_finished() && start.elapsed() < Duration::from_secs(10) {
+            std::thread::yield_now();
+        }
+
+        assert!(worker.is_finished());
+        assert_eq!(worker.join().unwrap(), 1);
+        assert_eq!(
+            event_loop
+                .call_method1(intern!(py, "run_until_complete"), (task,))?
+                .extract::<u64>()?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        event_loop.call_method0(intern!(py, "close"))?;
+    })
+}
+
+#[test]
+fn test_gxhash64_trio_cancelled_task_is_skipped() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let bytes = PyBytes::new(py, &vec![0u8; 16 << 20]);
+        let cancel = PyModule::from_code(
+            py,
+            cr"
+import trio
+
+async def cancel(hasher, data):
+    with trio.move_on_after(0.01) as scope:
+        await hasher.hash_async(data)
+
+    return scope.cancelled_caught
+",
+            c"cancel.py",
+            c"cancel",
+        )?
+        .getattr(intern!(py, "cancel"))?;
+
+        assert!(
+            py.import_trio()?
+                .call_method1(intern!(py, "run"), (cancel, &hasher, &bytes))?
+                .extract::<bool>()?
+        );
+
+        let start = Instant::now();
+        assert_eq!(run_deferred(&QUEUE, true), 1);
+        let skipping = start.elapsed();
+
+        let start = Instant::now();
+        hasher.call_method1(intern!(py, "hash"), (&bytes,))?;
+        let hashing = start.elapsed();
+
+        assert!(skipping * 10 < hashing);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_without_event_loop() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let coroutine = hasher.call_method1(intern!(py, "hash_async"), (b"hello",))?;
+        let error = coroutine.call_method1(intern!(py, "send"), (py.None(),)).unwrap_err();
+
+        assert!(error.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+        assert_eq!(run_deferred(&QUEUE, false), 1);
+    })
+}
+
+#[test]
+fn test_gxhash64_hash_async_timeout_expires() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let gxhash = py.import_gxhash()?;
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let items = vec![b"hello".to_vec(); 8];
+        let errors = [
+            call_hash_async_with_timeout::<u64>(py, &hasher, b"hello", 0.01).unwrap_err(),
+            call_hash_many_async_with_timeout::<Vec<u64>>(py, &hasher, &items, 0.01).unwrap_err(),
+        ];
+
+        for error in errors {
+            assert!(error.matches(py, gxhash.getattr(intern!(py, "GxHashTimeoutError"))?)?);
+            assert!(error.matches(py, gxhash.getattr(intern!(py, "GxHashAsyncError"))?)?);
+        }
+
+        assert_eq!(run_deferred(&QUEUE, false), 3);
+    })
+}
+
+#[test]
+fn test_gxhash64_cancelled_submit_is_skipped() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let bytes = PyBytes::new(py, &vec![0u8; 16 << 20]);
+        let future = hasher.call_method1(intern!(py, "hash_submit"), (&bytes,))?;
+
+        assert!(future.call_method0(intern!(py, "cancel"))?.extract::<bool>()?);
+
+        let start = Instant::now();
+        assert_eq!(run_deferred(&QUEUE, true), 1);
+        let skipping = start.elapsed();
+
+        let start = Instant::now();
+        hasher.call_method1(intern!(py, "hash"), (&bytes,))?;
+        let hashing = start.elapsed();
+
+        assert!(skipping * 10 < hashing);
+        assert!(future.call_method0(intern!(py, "cancelled"))?.extract::<bool>()?);
+    })
+}
+
+#[test]
+fn test_gxhash64_dropped_submit_fails() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+        let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+        kwargs.set_item("executor", &executor)?;
+        let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+        let future = hasher.call_method1(intern!(py, "hash_submit"), (b"hello",))?;
+
+        assert_eq!(run_deferred(&QUEUE, false), 1);
+        assert!(
+            future
+                .call_method0(intern!(py, "exception"))?
+                .is_instance(&py.import_gxhash()?.getattr(intern!(py, "GxHashAsyncError"))?)?
+        );
+    })
+}
+
+#[test]
+fn test_runtime_executor() -> PyResult<()> {
+    pytest!(py, {
+        let executor = py.import_gxhash()?.getattr(intern!(py, "RuntimeExecutor"))?.call0()?;
+        let hasher = py.import_gxhash64()?.call1((42,))?;
+        let hash = hasher.getattr(intern!(py, "hash"))?;
+        let int = py.get_type::<pyo3::types::PyInt>();
+        let large = vec![0u8; 4 << 20];
+        let items = PyList::new(py, [&b"hello"[..], b"world", &large])?;
+
+        assert_eq!(
+            executor
+                .call_method1(intern!(py, "submit"), (&hash, &b"hello"[..]))?
+                .call_method0(intern!(py, "result"))?
+                .extract::<u64>()?,
+            call_hash::<u64>(py, &hasher, b"hello")?
+        );
+        assert_eq!(
+            executor
+                .call_method1(intern!(py, "map"), (&hash, &items))?
+                .try_iter()?
+                .map(|hash| hash?.extract::<u64>())
+                .collect::<PyResult<Vec<_>>>()?,
+            hasher
+                .call_method1(intern!(py, "hash_many"), (&items,))?
+                .extract::<Vec<u64>>()?
+        );
+        assert_eq!(
+            executor
+                .call_method(
+                    intern!(py, "submit"),
+                    (&int, "ff"),
+                    Some(&[("base", 16)].into_py_dict(py)?)
+                )?
+                .call_method0(intern!(py, "result"))?
+                .extract::<u64>()?,
+            255
+        );
+        assert!(
+            executor
+                .call_method1(intern!(py, "submit"), (&int, "x"))?
+                .call_method0(intern!(py, "exception"))?
+                .is_instance_of::<pyo3::exceptions::PyValueError>()
+        );
+
+        executor.call_method0(intern!(py, "shutdown"))?;
+
+        assert!(
+            executor
+                .call_method1(intern!(py, "submit"), (&hash, &b"hello"[..]))
+                .unwrap_err()
+                .is_instance_of::<pyo3::exceptions::PyRuntimeError>(py)
+        );
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_max_in_flight() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            let queue_depth = gxhash.getattr(intern!(py, "queue_depth"))?;
+            let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            kwargs.set_item("executor", &executor)?;
+            let deferred = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            let items = vec![b"hello".to_vec(); 8];
+
+            assert_eq!(queue_depth.call0()?.extract::<usize>()?, 0);
+            gxhash.call_method(
+                intern!(py, "configure_runtime"),
+                (),
+                Some(&[("max_in_flight", 1)].into_py_dict(py)?),
+            )?;
+
+            let coroutines = vec![
+                deferred.call_method1(intern!(py, "hash_async"), (b"hello",))?,
+                deferred.call_method1(intern!(py, "hash_async"), (b"hello",))?,
+                deferred.call_method1(intern!(py, "hash_many_async"), (vec![b"hello"],))?,
+            ];
+
+            assert_eq!(queue_depth_when_saturated(py, coroutines)?, 2);
+            assert_eq!(queue_depth.call0()?.extract::<usize>()?, 0);
+            assert_eq!(run_deferred(&QUEUE, false), 1);
+            assert_eq!(
+                call_hash_async::<u64>(py, &hasher, b"hello")?,
+                call_hash::<u64>(py, &hasher, b"hello")?
+            );
+            assert_eq!(
+                call_hash_many_async::<Vec<u64>>(py, &hasher, &items)?,
+                call_hash_many::<Vec<u64>>(py, &hasher, &items)?
+            );
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_max_in_flight_bytes() -> PyResult<()> {
+    static QUEUE: Queue = Mutex::new(Vec::new());
+
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let executor = foreign_executor(py, &QUEUE, deferring_spawn_blocking)?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            kwargs.set_item("executor", &executor)?;
+            let deferred = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            let limit = PyBytes::new(py, &vec![0u8; 1 << 20]);
+            let larger = PyBytes::new(py, &vec![0u8; 4 << 20]);
+
+            py.import_gxhash()?.call_method(
+                intern!(py, "configure_runtime"),
+                (),
+                Some(&[("max_in_flight_bytes", 1 << 20)].into_py_dict(py)?),
+            )?;
+
+            let coroutines = vec![
+                deferred.call_method1(intern!(py, "hash_async"), (&limit,))?,
+                deferred.call_method1(intern!(py, "hash_async"), (b"hello",))?,
+                deferred.call_method1(intern!(py, "hash_async"), (&larger,))?,
+            ];
+
+            assert_eq!(queue_depth_when_saturated(py, coroutines)?, 2);
+            assert_eq!(run_deferred(&QUEUE, false), 1);
+
+            let coroutines = vec![deferred.call_method1(intern!(py, "hash_async"), (&larger,))?];
+
+            assert_eq!(queue_depth_when_saturated(py, coroutines)?, 0);
+            assert_eq!(run_deferred(&QUEUE, false), 1);
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_foreign_executor_leaves_runtime_down() -> PyResult<()> {
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let configure_runtime = py.import_gxhash()?.getattr(intern!(py, "configure_runtime"))?;
+            let executor = foreign_executor(py, &SPAWNED, thread_spawn_blocking)?;
+            let hasher = py
+                .import_gxhash64()?
+                .call((42,), Some(&[("executor", executor)].into_py_dict(py)?))?;
+            let bytes = vec![0u8; 8 << 20];
+            let items = vec![bytes.clone(); 2];
+            let expected = call_hash::<u64>(py, &hasher, &bytes)?;
+
+            configure_runtime.call((), Some(&[("max_in_flight", 4)].into_py_dict(py)?))?;
+            assert_eq!(
+                call_hash_async_with_timeout::<u64>(py, &hasher, &bytes, 60.0)?,
+                expected
+            );
+            assert_eq!(
+                call_hash_many_async_with_timeout::<Vec<u64>>(py, &hasher, &items, 60.0)?,
+                vec![expected; 2]
+            );
+            assert_eq!(call_hash_submit::<u64>(py, &hasher, &bytes)?, expected);
+            call_hash_parallel::<u64>(py, &hasher, &bytes, 1 << 20)?;
+
+            // the runtime can only be reconfigured while it is not running
+            configure_runtime.call((), Some(&[("worker_threads", 2)].into_py_dict(py)?))?;
+            assert!(SPAWNED.load(Ordering::Relaxed) > 0);
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_hash_parallel_on_runtime_executor() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            gxhash.call_method(
+                intern!(py, "configure_runtime"),
+                (),
+                Some(&[("worker_threads", 2)].into_py_dict(py)?),
+            )?;
+
+            let executor = gxhash.getattr(intern!(py, "RuntimeExecutor"))?.call0()?;
+            let hasher = py.import_gxhash64()?.call1((42,))?;
+            let bytes = vec![0u8; 4 << 20];
+            let expected = call_hash_parallel::<u64>(py, &hasher, &bytes, 1 << 20)?;
+            let kwargs = [("chunk_size", 1 << 20)].into_py_dict(py)?;
+            let hash_parallel = hasher.getattr(intern!(py, "hash_parallel"))?;
+
+            // every thread of the runtime waits on a `hash_parallel`, so none is left to hash their leaves
+            let futures = (0..2)
+                .map(|_| {
+                    executor.call_method(
+                        intern!(py, "submit"),
+                        (&hash_parallel, PyBytes::new(py, &bytes)),
+                        Some(&kwargs),
+                    )
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+
+            for future in futures {
+                assert_eq!(
+                    future.call_method1(intern!(py, "result"), (60,))?.extract::<u64>()?,
+                    expected
+                );
+            }
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gxhash64_hash_submit_on_runtime_executor() -> PyResult<()> {
+    pytest!(py, {
+        let status = run_in_child(py, |py| {
+            let gxhash = py.import_gxhash()?;
+            gxhash.call_method(
+                intern!(py, "configure_runtime"),
+                (),
+                Some(&[("worker_threads", 1)].into_py_dict(py)?),
+            )?;
+
+            let executor = gxhash.getattr(intern!(py, "RuntimeExecutor"))?.call0()?;
+            let kwargs = [("offload_threshold", "always")].into_py_dict(py)?;
+            let hasher = py.import_gxhash64()?.call((42,), Some(&kwargs))?;
+            let submit_and_wait = PyModule::from_code(
+                py,
+                c"def submit_and_wait(hasher, data):\n    return hasher.hash_submit(data).result()",
+                c"submit_and_wait.py",
+                c"submit_and_wait",
+            )?
+            .getattr(intern!(py, "submit_and_wait"))?;
+
+            // the only thread of the runtime waits on the `hash_submit`, so none is left to run it
+            let future = executor.call_method1(
+                intern!(py, "submit"),
+                (submit_and_wait, &hasher, PyBytes::new(py, b"hello")),
+            )?;
+
+            assert_eq!(
+                future.call_method1(intern!(py, "result"), (60,))?.extract::<u64>()?,
+                call_hash::<u64>(py, &hasher, b"hello")?
+            );
+
+            Ok(())
+        })?;
+
+        assert_eq!(status, 0);
+    })
+}
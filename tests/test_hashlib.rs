@@ -9,6 +9,14 @@ use pyo3::PyResult;
 use pyo3::intern;
 use pyo3::types::IntoPyDict;
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
+use pyo3::types::PyDictMethods;
+use pyo3::types::PyList;
+use pyo3::types::PyMemoryView;
+use pyo3::types::PyModule;
+use pyo3::types::PySlice;
 use pyo3::types::PyType;
 use pyo3::types::PyTypeMethods;
 use quickcheck_macros::quickcheck;
@@ -194,6 +202,287 @@ fn test_hashlib_gxhash128_update() -> PyResult<()> {
     })
 }
 
+#[quickcheck]
+fn test_hashlib_gxhash64_interleaved_updates(head: Vec<u8>, items: Vec<Vec<u8>>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_hashlib_gxhash64()?;
+        let hasher = gxhash64.call1((head.as_slice(),))?;
+        let mut combined = head.clone();
+
+        for item in &items {
+            let copy = hasher.call_method0(intern!(py, "copy"))?;
+            let copy_digest = copy.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?;
+
+            hasher.call_method1(intern!(py, "update"), (item.as_slice(),))?;
+            combined.extend_from_slice(item);
+
+            let digest = hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?;
+            let expected = call_hashlib_digest(py, &gxhash64, &combined)?;
+
+            assert_eq!(digest, expected);
+            assert_eq!(
+                hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                expected
+            );
+            assert_eq!(
+                copy.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                copy_digest
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash64_memory_budget(head: Vec<u8>, items: Vec<Vec<u8>>, budget: u8) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_hashlib_gxhash64()?;
+        let kwargs = [("memory_budget", budget)].into_py_dict(py)?;
+        let hasher = gxhash64.call((head.as_slice(),), Some(&kwargs))?;
+        let mut combined = head.clone();
+
+        for item in &items {
+            let copy = hasher.call_method0(intern!(py, "copy"))?;
+            let copy_digest = copy.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?;
+
+            hasher.call_method1(intern!(py, "update"), (item.as_slice(),))?;
+            combined.extend_from_slice(item);
+
+            let digest = hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?;
+
+            assert_eq!(digest, call_hashlib_digest(py, &gxhash64, &combined)?);
+            assert_eq!(
+                copy.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                copy_digest
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash128_forked_copies(head: Vec<u8>, suffixes: Vec<Vec<u8>>, budget: Option<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash128 = py.import_hashlib_gxhash128()?;
+        let kwargs = [("memory_budget", budget)].into_py_dict(py)?;
+        let hasher = gxhash128.call((head.as_slice(),), Some(&kwargs))?;
+        let mut forks = Vec::new();
+
+        for suffix in &suffixes {
+            let fork = hasher.call_method0(intern!(py, "copy"))?;
+            let nested = fork.call_method0(intern!(py, "copy"))?;
+
+            fork.call_method1(intern!(py, "update"), (suffix.as_slice(),))?;
+            nested.call_method1(intern!(py, "update"), (suffix.as_slice(),))?;
+            nested.call_method1(intern!(py, "update"), (suffix.as_slice(),))?;
+            hasher.call_method1(intern!(py, "update"), (suffix.as_slice(),))?;
+            forks.push((fork, nested, suffix));
+        }
+
+        let mut prefix = head.clone();
+
+        for (fork, nested, suffix) in forks {
+            prefix.extend_from_slice(suffix);
+            let mut doubled = prefix.clone();
+            doubled.extend_from_slice(suffix);
+
+            assert_eq!(
+                fork.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                call_hashlib_digest(py, &gxhash128, &prefix)?
+            );
+            assert_eq!(
+                nested.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                call_hashlib_digest(py, &gxhash128, &doubled)?
+            );
+        }
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+            call_hashlib_digest(py, &gxhash128, &prefix)?
+        );
+    })
+}
+
+#[test]
+fn test_hashlib_memory_budget_spilled() -> PyResult<()> {
+    pytest!(py, {
+        let gxhash128 = py.import_hashlib_gxhash128()?;
+        let head = (0..=u8::MAX).cycle().take(3 << 20).collect::<Vec<u8>>();
+        let kwargs = [("memory_budget", 1 << 20)].into_py_dict(py)?;
+        let hasher = gxhash128.call((head.as_slice(),), Some(&kwargs))?;
+        let mut combined = head.clone();
+
+        for item in head.chunks(1 << 16).take(24) {
+            hasher.call_method1(intern!(py, "update"), (item,))?;
+            combined.extend_from_slice(item);
+        }
+
+        let copy = hasher.call_method0(intern!(py, "copy"))?;
+        let expected = call_hashlib_hexdigest(py, &gxhash128, &combined)?;
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+            expected
+        );
+        assert_eq!(
+            copy.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+            expected
+        );
+
+        hasher.call_method1(intern!(py, "update"), (b"tail",))?;
+        combined.extend_from_slice(b"tail");
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+            call_hashlib_hexdigest(py, &gxhash128, &combined)?
+        );
+        assert_eq!(
+            copy.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+            expected
+        );
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_constructor_snapshots_data(bytes: Vec<u8>, mutation: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhashlib = py.import_gxhash_hashlib()?;
+
+        for name in ["gxhash32", "gxhash64", "gxhash128"] {
+            let constructor = gxhashlib.getattr(name)?;
+            let expected = call_hashlib_digest(py, &constructor, &bytes)?;
+            let data = PyByteArray::new(py, &bytes);
+            let hasher = constructor.call1((&data,))?;
+            let created = gxhashlib.call_method1(intern!(py, "new"), (name, &data))?;
+
+            data.call_method1(intern!(py, "reverse"), ())?;
+            data.call_method1(intern!(py, "extend"), (mutation.as_slice(),))?;
+
+            assert_eq!(
+                hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                expected
+            );
+            assert_eq!(
+                created.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+                expected
+            );
+        }
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash64_update_snapshots_data(head: Vec<u8>, bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_hashlib_gxhash64()?;
+        let hasher = gxhash64.call1((head.as_slice(),))?;
+        let data = PyByteArray::new(py, &bytes);
+        let mut combined = head.clone();
+        combined.extend_from_slice(&bytes);
+
+        hasher.call_method1(intern!(py, "update"), (&data,))?;
+        data.call_method1(intern!(py, "extend"), (b"mutated",))?;
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+            call_hashlib_digest(py, &gxhash64, &combined)?
+        );
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash64_lazy_borrows_data(bytes: Vec<u8>) -> PyResult<()> {
+    pytest!(py, {
+        let gxhash64 = py.import_hashlib_gxhash64()?;
+        let data = PyByteArray::new(py, &bytes);
+        let kwargs = [("lazy", true)].into_py_dict(py)?;
+        let hasher = gxhash64.call((&data,), Some(&kwargs))?;
+        let mut reversed = bytes.clone();
+        reversed.reverse();
+
+        data.call_method1(intern!(py, "reverse"), ())?;
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "digest"))?.extract::<Vec<u8>>()?,
+            call_hashlib_digest(py, &gxhash64, &reversed)?
+        );
+
+        let error = data.call_method1(intern!(py, "extend"), (b"mutated",)).unwrap_err();
+        assert!(error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+    })
+}
+
+#[test]
+fn test_hashlib_snapshot_releases_data() -> PyResult<()> {
+    pytest!(py, {
+        let data = PyByteArray::new(py, b"hello");
+        let hasher = py.import_hashlib_gxhash128()?.call1((&data,))?;
+
+        data.call_method1(intern!(py, "extend"), (b" world",))?;
+
+        assert_eq!(
+            hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+            call_hashlib_hexdigest(py, &py.import_hashlib_gxhash128()?, b"hello")?
+        );
+    })
+}
+
+#[test]
+fn test_hashlib_gxhash64_non_contiguous_buffer() -> PyResult<()> {
+    pytest!(py, {
+        let constructor = py.import_hashlib_gxhash64()?;
+        let memoryview = PyMemoryView::from(&PyByteArray::new(py, b"hello"))?;
+        let strided = memoryview.get_item(PySlice::new(py, 0, 5, 2))?;
+        let constructor_error = constructor.call1((&strided,)).unwrap_err();
+        let update_error = constructor
+            .call0()?
+            .call_method1(intern!(py, "update"), (&strided,))
+            .unwrap_err();
+
+        assert!(constructor_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+        assert!(update_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+    })
+}
+
+#[test]
+fn test_hashlib_gxhash64_shared_between_threads() -> PyResult<()> {
+    pytest!(py, {
+        let update_threaded = PyModule::from_code(
+            py,
+            cr"
+from concurrent.futures import ThreadPoolExecutor
+
+def update_threaded(hasher):
+    def update():
+        for _ in range(100):
+            hasher.update(b'x')
+            hasher.hexdigest()
+            hasher.copy()
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        for future in [pool.submit(update) for _ in range(4)]:
+            future.result()
+
+    return hasher.hexdigest()
+",
+            c"update_threaded.py",
+            c"update_threaded",
+        )?
+        .getattr(intern!(py, "update_threaded"))?;
+
+        for streaming in [false, true] {
+            let kwargs = [("streaming", streaming)].into_py_dict(py)?;
+            let hasher = py.import_hashlib_gxhash64()?.call((), Some(&kwargs))?;
+            let expected = py
+                .import_hashlib_gxhash64()?
+                .call((PyBytes::new(py, &[b'x'; 400]),), Some(&kwargs))?
+                .call_method0(intern!(py, "hexdigest"))?;
+
+            assert_eq!(
+                update_threaded.call1((&hasher,))?.extract::<String>()?,
+                expected.extract::<String>()?
+            );
+        }
+    })
+}
+
 #[test]
 fn test_hashlib_gxhash32_copy() -> PyResult<()> {
     pytest!(py, {
@@ -494,6 +783,78 @@ fn test_hashlib_new_invalid_name() -> PyResult<()> {
     })
 }
 
+#[test]
+fn test_hashlib_install() -> PyResult<()> {
+    pytest!(py, {
+        let gxhashlib = py.import_gxhash_hashlib()?;
+        let hashlib = py.import(intern!(py, "hashlib"))?;
+        let original = hashlib.getattr(intern!(py, "new"))?;
+        let sha256 = original
+            .call1(("sha256", b"hello"))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        gxhashlib.call_method0(intern!(py, "install"))?;
+        gxhashlib.call_method0(intern!(py, "install"))?;
+
+        let new = hashlib.getattr(intern!(py, "new"))?;
+        let kwargs = [("seed", 42)].into_py_dict(py)?;
+
+        for name in ["gxhash32", "gxhash64", "gxhash128"] {
+            let constructor = gxhashlib.getattr(name)?;
+            let expected = constructor
+                .call((b"hello",), Some(&kwargs))?
+                .call_method0(intern!(py, "hexdigest"))?
+                .extract::<String>()?;
+
+            assert!(hashlib.getattr(intern!(py, "algorithms_available"))?.contains(name)?);
+            assert!(!hashlib.getattr(intern!(py, "algorithms_guaranteed"))?.contains(name)?);
+            assert_eq!(
+                new.call((name, b"hello"), Some(&kwargs))?
+                    .call_method0(intern!(py, "hexdigest"))?
+                    .extract::<String>()?,
+                expected
+            );
+
+            if hashlib.hasattr(intern!(py, "file_digest"))? {
+                let file = py
+                    .import(intern!(py, "io"))?
+                    .getattr(intern!(py, "BytesIO"))?
+                    .call1((b"hello",))?;
+                let hasher = hashlib.call_method1(intern!(py, "file_digest"), (file, name))?;
+
+                assert_eq!(
+                    hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?,
+                    call_hashlib_hexdigest(py, &constructor, b"hello")?
+                );
+            }
+        }
+
+        assert_eq!(
+            new.call1(("sha256", b"hello"))?
+                .call_method0(intern!(py, "hexdigest"))?
+                .extract::<String>()?,
+            sha256
+        );
+
+        gxhashlib.call_method0(intern!(py, "uninstall"))?;
+        gxhashlib.call_method0(intern!(py, "uninstall"))?;
+
+        assert!(hashlib.getattr(intern!(py, "new"))?.is(&original));
+
+        for name in ["gxhash32", "gxhash64", "gxhash128"] {
+            assert!(!hashlib.getattr(intern!(py, "algorithms_available"))?.contains(name)?);
+            assert!(!hashlib.getattr(intern!(py, "algorithms_guaranteed"))?.contains(name)?);
+            assert!(
+                original
+                    .call1((name,))
+                    .unwrap_err()
+                    .is_instance_of::<pyo3::exceptions::PyValueError>(py)
+            );
+        }
+    })
+}
+
 #[quickcheck]
 fn test_hashlib_file_digest_io_gxhash32(bytes: Vec<u8>) -> PyResult<()> {
     pytest!(py, {
@@ -566,6 +927,31 @@ fn test_hashlib_file_digest_bytesio_gxhash128(bytes: Vec<u8>) -> PyResult<()> {
     })
 }
 
+#[test]
+fn test_hashlib_file_digest_bytesio_borrowed() -> PyResult<()> {
+    pytest!(py, {
+        let file = py
+            .import(intern!(py, "io"))?
+            .getattr(intern!(py, "BytesIO"))?
+            .call1((b"hello",))?;
+        let hasher = py.import_hashlib_file_digest()?.call1((&file, "gxhash64"))?;
+
+        let write_error = file.call_method1(intern!(py, "write"), (b" world",)).unwrap_err();
+        let digest = hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+        drop(hasher);
+        file.call_method1(intern!(py, "write"), (b" world",))?;
+
+        assert!(write_error.is_instance_of::<pyo3::exceptions::PyBufferError>(py));
+        assert_eq!(
+            digest,
+            py.import_hashlib_gxhash64()?
+                .call1((b"hello",))?
+                .call_method0(intern!(py, "hexdigest"))?
+                .extract::<String>()?
+        );
+    })
+}
+
 #[quickcheck]
 fn test_hashlib_file_digest_bytesio_with_seed(bytes: Vec<u8>, seed: i64) -> PyResult<()> {
     pytest!(py, {
@@ -721,3 +1107,172 @@ fn test_hashlib_file_digest_bytesio_with_callable(bytes: Vec<u8>) -> PyResult<()
         assert_eq!(file_digest, direct_digest);
     })
 }
+
+#[quickcheck]
+fn test_hashlib_gxhash32_streaming_update(bytes: Vec<u8>, split: usize) -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+        let (head, tail) = bytes.split_at(split % (bytes.len() + 1));
+        let hasher = py.import_hashlib_gxhash32()?.call((head,), Some(&kwargs))?;
+        hasher.call_method1(intern!(py, "update"), (tail,))?;
+        let hexdigest = hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+
+        let combined_hexdigest = py
+            .import_hashlib_gxhash32()?
+            .call((bytes.as_slice(),), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(hexdigest, combined_hexdigest);
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash64_streaming_update(bytes: Vec<u8>, split: usize) -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+        let (head, tail) = bytes.split_at(split % (bytes.len() + 1));
+        let hasher = py.import_hashlib_gxhash64()?.call((head,), Some(&kwargs))?;
+        hasher.call_method1(intern!(py, "update"), (tail,))?;
+        let hexdigest = hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+
+        let combined_hexdigest = py
+            .import_hashlib_gxhash64()?
+            .call((bytes.as_slice(),), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(hexdigest, combined_hexdigest);
+    })
+}
+
+#[quickcheck]
+fn test_hashlib_gxhash128_streaming_update(bytes: Vec<u8>, split: usize) -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+        let (head, tail) = bytes.split_at(split % (bytes.len() + 1));
+        let hasher = py.import_hashlib_gxhash128()?.call((head,), Some(&kwargs))?;
+        hasher.call_method1(intern!(py, "update"), (tail,))?;
+        let hexdigest = hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+
+        let combined_hexdigest = py
+            .import_hashlib_gxhash128()?
+            .call((bytes.as_slice(),), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(hexdigest, combined_hexdigest);
+    })
+}
+
+#[test]
+fn test_hashlib_streaming_large_input_chunking() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+        let bytes = (0..=u8::MAX).cycle().take(300_000).collect::<Vec<_>>();
+        let hasher = py.import_hashlib_gxhash64()?.call((), Some(&kwargs))?;
+
+        for chunk in bytes.chunks(7_777) {
+            hasher.call_method1(intern!(py, "update"), (chunk,))?;
+        }
+
+        let hexdigest = hasher.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+        let combined_hexdigest = py
+            .import_hashlib_gxhash64()?
+            .call((bytes.as_slice(),), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(hexdigest, combined_hexdigest);
+    })
+}
+
+#[test]
+fn test_hashlib_streaming_copy() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+        let hasher1 = py.import_hashlib_gxhash128()?.call((b"hello",), Some(&kwargs))?;
+        let hasher2 = hasher1.call_method0(intern!(py, "copy"))?;
+        hasher1.call_method1(intern!(py, "update"), (b" world",))?;
+
+        let hexdigest1 = hasher1.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+        let hexdigest2 = hasher2.call_method0(intern!(py, "hexdigest"))?.extract::<String>()?;
+        let expected_hexdigest = py
+            .import_hashlib_gxhash128()?
+            .call((b"hello",), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_ne!(hexdigest1, hexdigest2);
+        assert_eq!(hexdigest2, expected_hexdigest);
+    })
+}
+
+#[test]
+fn test_hashlib_new_streaming() -> PyResult<()> {
+    pytest!(py, {
+        let kwargs = [("streaming", true)].into_py_dict(py)?;
+
+        let new_digest = py
+            .import_hashlib_new()?
+            .call(("gxhash64", b"hello"), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        let direct_digest = py
+            .import_hashlib_gxhash64()?
+            .call((b"hello",), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(new_digest, direct_digest);
+    })
+}
+
+#[test]
+fn test_hashlib_file_digest_progress() -> PyResult<()> {
+    pytest!(py, {
+        let path = std::env::temp_dir().join(format!("hashlib-file-digest-progress-{}", std::process::id()));
+        let bytes = (0..(3 << 20) + 5).map(|i: u32| i.to_le_bytes()[0]).collect::<Vec<_>>();
+        std::fs::write(&path, &bytes)?;
+
+        let file = py
+            .import(intern!(py, "io"))?
+            .call_method1(intern!(py, "open"), (&path, "rb"))?;
+        let reports = PyList::empty(py);
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("progress", reports.getattr("append")?)?;
+        kwargs.set_item("progress_interval", 1 << 20)?;
+
+        let file_digest = py
+            .import_hashlib_file_digest()?
+            .call((&file, "gxhash64"), Some(&kwargs))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+        file.call_method0(intern!(py, "close"))?;
+
+        let direct_digest = py
+            .import_hashlib_gxhash64()?
+            .call1((bytes.as_slice(),))?
+            .call_method0(intern!(py, "hexdigest"))?
+            .extract::<String>()?;
+
+        assert_eq!(file_digest, direct_digest);
+        assert_eq!(
+            reports.extract::<Vec<usize>>()?,
+            vec![1 << 20, 2 << 20, 3 << 20, bytes.len()]
+        );
+
+        let file = py
+            .import(intern!(py, "io"))?
+            .getattr(intern!(py, "BytesIO"))?
+            .call1((bytes.as_slice(),))?;
+        let reports = PyList::empty(py);
+        let kwargs = [("progress", reports.getattr("append")?)].into_py_dict(py)?;
+        py.import_hashlib_file_digest()?
+            .call((&file, "gxhash64"), Some(&kwargs))?;
+
+        assert_eq!(reports.extract::<Vec<usize>>()?, vec![bytes.len()]);
+        std::fs::remove_file(&path)?;
+    })
+}